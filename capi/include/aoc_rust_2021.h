@@ -0,0 +1,66 @@
+#ifndef AOC_RUST_2021_H
+#define AOC_RUST_2021_H
+
+/* Generated with cbindgen from capi/src/lib.rs, run `make header` to update. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define AOC_OK 0
+
+#define AOC_ERROR_NULL_POINTER 1
+
+#define AOC_ERROR_INVALID_UTF8 2
+
+#define AOC_ERROR_UNKNOWN_DAY 3
+
+#define AOC_ERROR_UNKNOWN_PART 4
+
+#define AOC_ERROR_SOLVER 5
+
+#define AOC_ERROR_PANIC 6
+
+/**
+ * Solve `part` of `day` on the `input_len` bytes at `input`.
+ *
+ * On success, `*answer` receives the answer as a NUL-terminated string and
+ * `AOC_OK` is returned. On failure, an error code is returned and `*answer`
+ * receives a description of the error, or NULL if there is none.
+ *
+ * # Safety
+ *
+ * `input` must point to `input_len` readable bytes and `answer` must be a
+ * valid pointer to write to. The string stored in `*answer` must be released
+ * with `aoc_string_free`.
+ */
+int32_t aoc_solve(uint8_t day, uint8_t part, const uint8_t *input, size_t input_len, char **answer);
+
+/**
+ * Release a string returned by `aoc_solve`. Passing NULL is a no-op.
+ *
+ * # Safety
+ *
+ * `string` must be NULL or a pointer obtained from this library that has not
+ * been freed yet.
+ */
+void aoc_string_free(char *string);
+
+/**
+ * Copy the available days into `buffer`, up to `capacity` of them, and
+ * return how many days are available in total.
+ *
+ * # Safety
+ *
+ * `buffer` must be NULL or point to `capacity` writable bytes.
+ */
+size_t aoc_days(uint8_t *buffer, size_t capacity);
+
+/**
+ * Static description of a status code returned by `aoc_solve`.
+ */
+const char *aoc_status_message(int32_t status);
+
+#endif  /* AOC_RUST_2021_H */
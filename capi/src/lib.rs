@@ -0,0 +1,195 @@
+//! C ABI over the `aoc_rust_2021` solvers.
+//!
+//! Strings handed out by this library are allocated by Rust and must be
+//! released with `aoc_string_free`. The header lives in `capi/include`.
+
+use aoc_rust_2021::{days, solve, AocError};
+use std::ffi::{c_char, CString};
+use std::panic;
+use std::ptr;
+use std::slice;
+use std::str;
+
+pub const AOC_OK: i32 = 0;
+pub const AOC_ERROR_NULL_POINTER: i32 = 1;
+pub const AOC_ERROR_INVALID_UTF8: i32 = 2;
+pub const AOC_ERROR_UNKNOWN_DAY: i32 = 3;
+pub const AOC_ERROR_UNKNOWN_PART: i32 = 4;
+pub const AOC_ERROR_SOLVER: i32 = 5;
+pub const AOC_ERROR_PANIC: i32 = 6;
+
+fn into_c_string(text: String) -> *mut c_char {
+    // Answers and error messages never contain NUL bytes, but strip them
+    // rather than fail if one ever does.
+    CString::new(text.replace('\0', ""))
+        .map(CString::into_raw)
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Solve `part` of `day` on the `input_len` bytes at `input`.
+///
+/// On success, `*answer` receives the answer as a NUL-terminated string and
+/// `AOC_OK` is returned. On failure, an error code is returned and `*answer`
+/// receives a description of the error, or NULL if there is none.
+///
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes and `answer` must be a
+/// valid pointer to write to. The string stored in `*answer` must be released
+/// with `aoc_string_free`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u8,
+    part: u8,
+    input: *const u8,
+    input_len: usize,
+    answer: *mut *mut c_char,
+) -> i32 {
+    if answer.is_null() {
+        return AOC_ERROR_NULL_POINTER;
+    }
+    *answer = ptr::null_mut();
+
+    if input.is_null() {
+        return AOC_ERROR_NULL_POINTER;
+    }
+
+    let input = match str::from_utf8(slice::from_raw_parts(input, input_len)) {
+        Ok(input) => input,
+        Err(_) => return AOC_ERROR_INVALID_UTF8,
+    };
+
+    match panic::catch_unwind(|| solve(day, part, input)) {
+        Ok(Ok(solution)) => {
+            *answer = into_c_string(solution.to_string());
+            AOC_OK
+        }
+        Ok(Err(error)) => {
+            *answer = into_c_string(error.to_string());
+            match error {
+                AocError::UnknownDay(_) => AOC_ERROR_UNKNOWN_DAY,
+                AocError::UnknownPart(_) => AOC_ERROR_UNKNOWN_PART,
+                AocError::Solver(_) => AOC_ERROR_SOLVER,
+            }
+        }
+        Err(_) => AOC_ERROR_PANIC,
+    }
+}
+
+/// Release a string returned by `aoc_solve`. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `string` must be NULL or a pointer obtained from this library that has not
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Copy the available days into `buffer`, up to `capacity` of them, and
+/// return how many days are available in total.
+///
+/// # Safety
+///
+/// `buffer` must be NULL or point to `capacity` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_days(buffer: *mut u8, capacity: usize) -> usize {
+    let available = days();
+
+    if !buffer.is_null() {
+        for (slot, day) in slice::from_raw_parts_mut(buffer, capacity)
+            .iter_mut()
+            .zip(available)
+        {
+            *slot = day.day;
+        }
+    }
+
+    available.len()
+}
+
+/// Static description of a status code returned by `aoc_solve`.
+#[no_mangle]
+pub extern "C" fn aoc_status_message(status: i32) -> *const c_char {
+    let message: &'static [u8] = match status {
+        AOC_OK => b"ok\0",
+        AOC_ERROR_NULL_POINTER => b"null pointer argument\0",
+        AOC_ERROR_INVALID_UTF8 => b"input is not valid UTF-8\0",
+        AOC_ERROR_UNKNOWN_DAY => b"unknown day\0",
+        AOC_ERROR_UNKNOWN_PART => b"unknown part\0",
+        AOC_ERROR_SOLVER => b"solver error\0",
+        AOC_ERROR_PANIC => b"solver panicked\0",
+        _ => b"unknown status\0",
+    };
+
+    message.as_ptr().cast()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        aoc_days, aoc_solve, aoc_status_message, aoc_string_free, AOC_ERROR_INVALID_UTF8,
+        AOC_ERROR_NULL_POINTER, AOC_ERROR_UNKNOWN_DAY, AOC_OK,
+    };
+    use std::ffi::CStr;
+    use std::ptr;
+
+    fn call_solve(day: u8, part: u8, input: &[u8]) -> (i32, Option<String>) {
+        let mut answer = ptr::null_mut();
+        let status = unsafe { aoc_solve(day, part, input.as_ptr(), input.len(), &mut answer) };
+        let text = (!answer.is_null()).then(|| unsafe {
+            let text = CStr::from_ptr(answer).to_string_lossy().into_owned();
+            aoc_string_free(answer);
+            text
+        });
+
+        (status, text)
+    }
+
+    #[test]
+    fn test_aoc_solve() {
+        assert_eq!(
+            call_solve(7, 2, b"16,1,2,0,4,2,7,1,2,14\n"),
+            (AOC_OK, Some(String::from("168")))
+        );
+    }
+
+    #[test]
+    fn test_aoc_solve_errors() {
+        assert_eq!(
+            call_solve(30, 1, b""),
+            (
+                AOC_ERROR_UNKNOWN_DAY,
+                Some(String::from("no solver available for day 30"))
+            )
+        );
+        assert_eq!(
+            call_solve(1, 1, &[0xff, 0xfe]),
+            (AOC_ERROR_INVALID_UTF8, None)
+        );
+        assert_eq!(
+            unsafe { aoc_solve(1, 1, ptr::null(), 0, ptr::null_mut()) },
+            AOC_ERROR_NULL_POINTER
+        );
+    }
+
+    #[test]
+    fn test_aoc_days() {
+        let mut buffer = [0u8; 3];
+        let count = unsafe { aoc_days(buffer.as_mut_ptr(), buffer.len()) };
+
+        assert_eq!(count, 11);
+        assert_eq!(buffer, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_aoc_status_message() {
+        assert_eq!(
+            unsafe { CStr::from_ptr(aoc_status_message(AOC_ERROR_UNKNOWN_DAY)) },
+            c"unknown day"
+        );
+    }
+}
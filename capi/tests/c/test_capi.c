@@ -0,0 +1,45 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "aoc_rust_2021.h"
+
+static int failures = 0;
+
+static void expect_answer(uint8_t day, uint8_t part, const char *input, int32_t expected_status,
+                          const char *expected_answer) {
+    char *answer = NULL;
+    int32_t status = aoc_solve(day, part, (const uint8_t *)input, strlen(input), &answer);
+
+    if (status != expected_status || answer == NULL || strcmp(answer, expected_answer) != 0) {
+        fprintf(stderr, "day %u part %u: expected %d \"%s\", got %d \"%s\" (%s)\n", day, part,
+                expected_status, expected_answer, status, answer ? answer : "(null)",
+                aoc_status_message(status));
+        failures++;
+    }
+
+    aoc_string_free(answer);
+}
+
+int main(void) {
+    uint8_t days[32];
+    size_t count = aoc_days(days, sizeof(days));
+
+    if (count != 11 || days[0] != 1 || days[10] != 11) {
+        fprintf(stderr, "unexpected list of days (%zu)\n", count);
+        failures++;
+    }
+
+    expect_answer(1, 1, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263", AOC_OK, "7");
+    expect_answer(1, 2, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263", AOC_OK, "5");
+    expect_answer(6, 2, "3,4,3,1,2\n", AOC_OK, "26984457539");
+    expect_answer(7, 1, "16,1,2,0,4,2,7,1,2,14\n", AOC_OK, "37");
+    expect_answer(26, 1, "", AOC_ERROR_UNKNOWN_DAY, "no solver available for day 26");
+    expect_answer(10, 2, "", AOC_ERROR_SOLVER, "Could not find middle score");
+
+    if (aoc_solve(1, 1, NULL, 0, NULL) != AOC_ERROR_NULL_POINTER) {
+        fprintf(stderr, "NULL arguments were not rejected\n");
+        failures++;
+    }
+
+    return failures == 0 ? 0 : 1;
+}
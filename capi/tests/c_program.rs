@@ -0,0 +1,42 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory holding the libraries built for this package, i.e. `target/<profile>`.
+fn artifacts_directory() -> PathBuf {
+    let test_executable = env::current_exe().unwrap();
+
+    // Test executables are built in `target/<profile>/deps`.
+    test_executable
+        .parent()
+        .and_then(Path::parent)
+        .unwrap()
+        .to_path_buf()
+}
+
+#[test]
+fn test_c_program() {
+    let manifest_directory = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let executable = Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_capi");
+
+    let compilation = Command::new(env::var("CC").unwrap_or_else(|_| String::from("cc")))
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_directory.join("include"))
+        .arg(manifest_directory.join("tests/c/test_capi.c"))
+        .arg(artifacts_directory().join("libaoc_rust_2021_capi.a"))
+        .args(["-lpthread", "-ldl", "-lm"])
+        .arg("-o")
+        .arg(&executable)
+        .status()
+        .expect("a C compiler is required to run this test");
+    assert!(compilation.success());
+
+    let run = Command::new(&executable).output().unwrap();
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use aoc_rust_2021::day2::{parse_course, Navigator, Submarine, SubmarineV2};
+use aoc_rust_2021::find_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let day = find_day(2).unwrap();
+
+    if let Ok(course) = parse_course(input) {
+        let _ = Submarine::new().follow(&course);
+        let _ = SubmarineV2::new().follow(&course);
+    }
+
+    for part in 1..=2 {
+        let _ = day.solve(part, input);
+        let _ = day.explain(part, input);
+    }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+use aoc_rust_2021::day3::{co2_scrubber_rating, oxygen_generator_rating, BitString};
+use aoc_rust_2021::find_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let day = find_day(3).unwrap();
+    let binary_numbers: Vec<BitString> = input
+        .lines()
+        .filter_map(|line| line.parse().ok())
+        .collect();
+
+    let _ = oxygen_generator_rating(&binary_numbers);
+    let _ = co2_scrubber_rating(&binary_numbers);
+
+    for part in 1..=2 {
+        let _ = day.solve(part, input);
+        let _ = day.explain(part, input);
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use aoc_rust_2021::day4::BingoSubsystem;
+use aoc_rust_2021::find_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let day = find_day(4).unwrap();
+
+    let _ = BingoSubsystem::new(input);
+
+    for part in 1..=2 {
+        let _ = day.solve(part, input);
+        let _ = day.explain(part, input);
+    }
+});
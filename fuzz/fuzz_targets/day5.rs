@@ -0,0 +1,18 @@
+#![no_main]
+
+use aoc_rust_2021::day5::extract_coordinates_from_line;
+use aoc_rust_2021::find_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let day = find_day(5).unwrap();
+
+    for line in input.lines() {
+        let _ = extract_coordinates_from_line(line);
+    }
+
+    for part in 1..=2 {
+        let _ = day.solve(part, input);
+        let _ = day.explain(part, input);
+    }
+});
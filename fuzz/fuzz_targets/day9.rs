@@ -0,0 +1,18 @@
+#![no_main]
+
+use aoc_rust_2021::day9::{lowest_points, parse_input, Basins};
+use aoc_rust_2021::find_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let day = find_day(9).unwrap();
+    let map = parse_input(input);
+
+    let _ = Basins::new(&map);
+    let _ = lowest_points(map);
+
+    for part in 1..=2 {
+        let _ = day.solve(part, input);
+        let _ = day.explain(part, input);
+    }
+});
@@ -0,0 +1,257 @@
+use crate::cli::inputs::Inputs;
+use crate::cli::{panic_message, truncate};
+use aoc_rust_2021::days;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::panic;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const ERROR_WIDTH: usize = 32;
+
+const RESET: &str = "\x1b[0m";
+const RED: &str = "\x1b[31m";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    Solved { answer: String, time: Duration },
+    Failed { error: String },
+}
+
+struct Row {
+    day: u8,
+    part: u8,
+    cells: Vec<Cell>,
+}
+
+/// Name of every subfolder of `folder`, sorted.
+fn input_sets(folder: &Path) -> Result<Vec<String>, String> {
+    let error = |error: io::Error| format!("{}: {}", folder.display(), error);
+    let mut sets = Vec::new();
+
+    for entry in fs::read_dir(folder).map_err(error)? {
+        let entry = entry.map_err(error)?;
+
+        if entry.file_type().map_err(error)?.is_dir() {
+            sets.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    sets.sort();
+
+    Ok(sets)
+}
+
+fn solve_all(folder: &Path, sets: &[String]) -> Vec<Row> {
+    let inputs: Vec<Inputs> = sets
+        .iter()
+        .map(|set| Inputs::Folder(folder.join(set)))
+        .collect();
+    let mut rows = Vec::new();
+
+    for day in days() {
+        let day_inputs: Vec<Result<String, String>> =
+            inputs.iter().map(|inputs| inputs.load(day.day)).collect();
+
+        for part in 1..=2 {
+            let cells = day_inputs
+                .iter()
+                .map(|input| {
+                    let input = match input {
+                        Ok(input) => input,
+                        Err(error) => {
+                            return Cell::Failed {
+                                error: error.clone(),
+                            }
+                        }
+                    };
+
+                    let chrono_start = Instant::now();
+                    let solution_result = panic::catch_unwind(|| day.solve(part, input));
+                    let time = chrono_start.elapsed();
+
+                    match solution_result {
+                        Ok(Ok(answer)) => Cell::Solved {
+                            answer: answer.to_string(),
+                            time,
+                        },
+                        Ok(Err(error)) => Cell::Failed {
+                            error: format!("error: {}", error),
+                        },
+                        Err(payload) => Cell::Failed {
+                            error: panic_message(payload),
+                        },
+                    }
+                })
+                .collect();
+
+            rows.push(Row {
+                day: day.day,
+                part,
+                cells,
+            });
+        }
+    }
+
+    rows
+}
+
+fn render(sets: &[String], rows: &[Row], colour: bool) -> Vec<String> {
+    let text = |cell: &Cell| match cell {
+        Cell::Solved { answer, time } => format!("{} ({}μs)", answer, time.as_micros()),
+        Cell::Failed { error } => format!("✗ {}", truncate(error, ERROR_WIDTH)),
+    };
+    let widths: Vec<usize> = sets
+        .iter()
+        .enumerate()
+        .map(|(column, set)| {
+            rows.iter()
+                .map(|row| text(&row.cells[column]).chars().count())
+                .chain([set.chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut header = format!("{:>3} {:>4}", "Day", "Part");
+    for (set, width) in sets.iter().zip(&widths) {
+        header.push_str(&format!("  {:<width$}", set, width = width));
+    }
+    let mut lines = vec![header.trim_end().to_string()];
+
+    for row in rows {
+        let mut line = format!("{:>3} {:>4}", row.day, row.part);
+
+        for (cell, width) in row.cells.iter().zip(&widths) {
+            let cell_text = format!("{:<width$}", text(cell), width = width);
+
+            match cell {
+                Cell::Failed { .. } if colour => {
+                    line.push_str(&format!("  {}{}{}", RED, cell_text, RESET))
+                }
+                _ => line.push_str(&format!("  {}", cell_text)),
+            }
+        }
+        lines.push(line.trim_end().to_string());
+    }
+
+    let failures: Vec<String> = rows
+        .iter()
+        .flat_map(|row| {
+            row.cells
+                .iter()
+                .zip(sets)
+                .filter_map(move |(cell, set)| match cell {
+                    Cell::Failed { error } => Some(format!(
+                        "  {}, Day {}, Part {}: {}",
+                        set, row.day, row.part, error
+                    )),
+                    Cell::Solved { .. } => None,
+                })
+        })
+        .collect();
+
+    if !failures.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("Failures ({}):", failures.len()));
+        lines.extend(failures);
+    }
+
+    lines
+}
+
+pub fn main(args: &[String]) -> Result<(), String> {
+    let folder = match args {
+        [folder] => Path::new(folder),
+        _ => return Err(String::from("usage: aoc_rust_2021 batch <inputs folder>")),
+    };
+    let sets = input_sets(folder)?;
+
+    if sets.is_empty() {
+        return Err(format!(
+            "{}: no input sets (expected one folder per set)",
+            folder.display()
+        ));
+    }
+
+    // Panics are reported in the matrix, not in the middle of it.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let rows = solve_all(folder, &sets);
+    panic::set_hook(default_hook);
+
+    for line in render(&sets, &rows, io::stdout().is_terminal()) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::batch::{input_sets, render, Cell, Row};
+    use std::env;
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn test_input_sets() {
+        let folder = env::temp_dir().join(format!("aoc_rust_2021-batch-{}", std::process::id()));
+        fs::create_dir_all(folder.join("bob")).unwrap();
+        fs::create_dir_all(folder.join("alice")).unwrap();
+        fs::write(folder.join("notes.txt"), "").unwrap();
+
+        assert_eq!(
+            input_sets(&folder),
+            Ok(vec![String::from("alice"), String::from("bob")])
+        );
+
+        fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn test_render() {
+        let sets = vec![String::from("alice"), String::from("bob")];
+        let rows = vec![
+            Row {
+                day: 1,
+                part: 1,
+                cells: vec![
+                    Cell::Solved {
+                        answer: String::from("7"),
+                        time: Duration::from_micros(12),
+                    },
+                    Cell::Solved {
+                        answer: String::from("1316"),
+                        time: Duration::from_micros(250),
+                    },
+                ],
+            },
+            Row {
+                day: 10,
+                part: 2,
+                cells: vec![
+                    Cell::Solved {
+                        answer: String::from("288957"),
+                        time: Duration::from_micros(40),
+                    },
+                    Cell::Failed {
+                        error: String::from("panicked: index out of bounds"),
+                    },
+                ],
+            },
+        ];
+
+        assert_eq!(
+            render(&sets, &rows, false),
+            vec![
+                "Day Part  alice          bob",
+                "  1    1  7 (12μs)       1316 (250μs)",
+                " 10    2  288957 (40μs)  ✗ panicked: index out of bounds",
+                "",
+                "Failures (1):",
+                "  bob, Day 10, Part 2: panicked: index out of bounds",
+            ]
+        );
+        assert!(render(&sets, &rows, true)[2].contains("\x1b[31m✗ panicked"));
+    }
+}
@@ -0,0 +1,133 @@
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// On-disk cache of answers, keyed by day, part and a hash of the input and of
+/// the crate version, so a new release never reads answers of an older one.
+pub struct Cache {
+    folder: PathBuf,
+}
+
+/// 64-bit FNV-1a hash of the crate version and `input`.
+fn hash(input: &str) -> u64 {
+    env!("CARGO_PKG_VERSION")
+        .bytes()
+        .chain([0])
+        .chain(input.bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+impl Cache {
+    pub fn new(folder: PathBuf) -> Self {
+        Self { folder }
+    }
+
+    /// `$XDG_CACHE_HOME/aoc_rust_2021`, or `~/.cache/aoc_rust_2021`.
+    pub fn default_folder() -> Result<PathBuf, String> {
+        let cache_home = match env::var_os("XDG_CACHE_HOME") {
+            Some(folder) if !folder.is_empty() => PathBuf::from(folder),
+            _ => env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join(".cache"))
+                .ok_or("cannot find the cache folder: HOME is not set")?,
+        };
+
+        Ok(cache_home.join("aoc_rust_2021"))
+    }
+
+    fn path(&self, day: u8, part: u8, input: &str) -> PathBuf {
+        self.folder
+            .join(format!("day{}-part{}-{:016x}.txt", day, part, hash(input)))
+    }
+
+    pub fn get(&self, day: u8, part: u8, input: &str) -> Option<String> {
+        fs::read_to_string(self.path(day, part, input)).ok()
+    }
+
+    pub fn put(&self, day: u8, part: u8, input: &str, answer: &str) -> Result<(), String> {
+        let path = self.path(day, part, input);
+
+        fs::create_dir_all(&self.folder)
+            .and_then(|_| fs::write(&path, answer))
+            .map_err(|error| format!("{}: {}", path.display(), error))
+    }
+
+    /// Remove every cached answer, returning how many there were.
+    pub fn clear(&self) -> Result<usize, String> {
+        let error = |error: std::io::Error| format!("{}: {}", self.folder.display(), error);
+        let entries = match fs::read_dir(&self.folder) {
+            Ok(entries) => entries,
+            Err(not_found) if not_found.kind() == ErrorKind::NotFound => return Ok(0),
+            Err(other) => return Err(error(other)),
+        };
+        let mut removed = 0;
+
+        for entry in entries {
+            let path = entry.map_err(error)?.path();
+
+            if path.extension().is_some_and(|extension| extension == "txt") {
+                fs::remove_file(&path).map_err(error)?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    pub fn folder(&self) -> &PathBuf {
+        &self.folder
+    }
+}
+
+pub fn main(args: &[String]) -> Result<(), String> {
+    match args {
+        [command] if command == "clear" => {
+            let cache = Cache::new(Cache::default_folder()?);
+            let removed = cache.clear()?;
+
+            println!(
+                "Removed {} cached answers from {}",
+                removed,
+                cache.folder().display()
+            );
+
+            Ok(())
+        }
+        _ => Err(String::from("usage: aoc_rust_2021 cache clear")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::cache::{hash, Cache};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash("199\n200\n"), hash("199\n200\n"));
+        assert_ne!(hash("199\n200\n"), hash("199\n201\n"));
+    }
+
+    #[test]
+    fn test_cache() {
+        let folder = env::temp_dir().join(format!("aoc_rust_2021-cache-{}", std::process::id()));
+        let cache = Cache::new(folder.clone());
+
+        assert_eq!(cache.clear(), Ok(0));
+        assert_eq!(cache.get(1, 1, "199\n200\n"), None);
+
+        cache.put(1, 1, "199\n200\n", "1").unwrap();
+        cache.put(1, 2, "199\n200\n", "0").unwrap();
+
+        assert_eq!(cache.get(1, 1, "199\n200\n"), Some(String::from("1")));
+        assert_eq!(cache.get(1, 2, "199\n200\n"), Some(String::from("0")));
+        assert_eq!(cache.get(1, 1, "199\n201\n"), None);
+        assert_eq!(cache.clear(), Ok(2));
+        assert_eq!(cache.get(1, 1, "199\n200\n"), None);
+
+        fs::remove_dir(folder).unwrap();
+    }
+}
@@ -0,0 +1,444 @@
+use crate::cli::option_value;
+use aoc_rust_2021::days;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const FILE_NAME: &str = "aoc.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "unknown format: {} (expected text or json)",
+                format
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(color: &str) -> Result<Self, Self::Err> {
+        match color {
+            "auto" => Ok(Color::Auto),
+            "always" => Ok(Color::Always),
+            "never" => Ok(Color::Never),
+            _ => Err(format!(
+                "unknown color: {} (expected auto, always or never)",
+                color
+            )),
+        }
+    }
+}
+
+/// Defaults of the runner, read from `aoc.toml` and overridden by flags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub input_dir: Option<String>,
+    pub format: Format,
+    /// How many times each part is solved, to average the timings.
+    pub iterations: u32,
+    /// Days to run, all of them when `None`.
+    pub days: Option<Vec<u8>>,
+    /// File with the expected answers, one `day part answer` per line.
+    pub answers: Option<String>,
+    pub color: Color,
+    pub cache: bool,
+    /// Configuration file the settings were read from.
+    pub source: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            input_dir: None,
+            format: Format::Text,
+            iterations: 1,
+            days: None,
+            answers: None,
+            color: Color::Auto,
+            cache: false,
+            source: None,
+        }
+    }
+}
+
+/// Text before a `#` that is not inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, character) in line.char_indices() {
+        match character {
+            '\\' if in_string && !escaped => {
+                escaped = true;
+                continue;
+            }
+            '"' if !escaped => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => {}
+        }
+        escaped = false;
+    }
+
+    line
+}
+
+fn parse_string(value: &str) -> Result<String, String> {
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a quoted string, found {}", value))?;
+    let mut string = String::new();
+    let mut characters = inner.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => match characters.next() {
+                Some('"') => string.push('"'),
+                Some('\\') => string.push('\\'),
+                Some('n') => string.push('\n'),
+                Some('t') => string.push('\t'),
+                _ => return Err(format!("unsupported escape sequence in {}", value)),
+            },
+            '"' => return Err(format!("unexpected quote in {}", value)),
+            character => string.push(character),
+        }
+    }
+
+    Ok(string)
+}
+
+fn parse_iterations(value: &str) -> Result<u32, String> {
+    match value.parse() {
+        Ok(0) | Err(_) => Err(format!(
+            "iterations must be a positive integer, found {}",
+            value
+        )),
+        Ok(iterations) => Ok(iterations),
+    }
+}
+
+fn parse_day(day: &str) -> Result<u8, String> {
+    day.trim()
+        .parse()
+        .map_err(|_| format!("invalid day: {}", day.trim()))
+}
+
+/// Days of a `days` array, e.g. `[1, 2, 5]`.
+fn parse_days_array(value: &str) -> Result<Vec<u8>, String> {
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+        .ok_or_else(|| format!("expected an array of days, found {}", value))?;
+
+    inner
+        .split(',')
+        .filter(|day| !day.trim().is_empty())
+        .map(parse_day)
+        .collect()
+}
+
+/// Days of a `--days` flag, e.g. `1,2,5-7`.
+fn parse_days_list(value: &str) -> Result<Vec<u8>, String> {
+    let mut days = Vec::new();
+
+    for range in value.split(',') {
+        match range.split_once('-') {
+            Some((first, last)) => days.extend(parse_day(first)?..=parse_day(last)?),
+            None => days.push(parse_day(range)?),
+        }
+    }
+
+    Ok(days)
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl FromStr for Config {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut config = Config::default();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            line.split_once('=')
+                .ok_or_else(|| String::from("expected key = value"))
+                .and_then(|(key, value)| config.set(key.trim(), value.trim()))
+                .map_err(|error| format!("line {}: {}", index + 1, error))?;
+        }
+
+        Ok(config)
+    }
+}
+
+impl Config {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "input_dir" => self.input_dir = Some(parse_string(value)?),
+            "format" => self.format = parse_string(value)?.parse()?,
+            "iterations" => self.iterations = parse_iterations(value)?,
+            "days" => self.days = Some(parse_days_array(value)?),
+            "answers" => self.answers = Some(parse_string(value)?),
+            "color" => self.color = parse_string(value)?.parse()?,
+            "cache" => {
+                self.cache = match value {
+                    "true" => true,
+                    "false" => false,
+                    _ => return Err(format!("expected true or false, found {}", value)),
+                }
+            }
+            _ => return Err(format!("unknown setting: {}", key)),
+        }
+
+        Ok(())
+    }
+
+    /// `aoc.toml` of the current folder, or of the user configuration folder
+    /// (`$XDG_CONFIG_HOME/aoc_rust_2021`, or `~/.config/aoc_rust_2021`).
+    fn candidate_paths() -> Vec<PathBuf> {
+        let mut paths = vec![PathBuf::from(FILE_NAME)];
+        let config_home = match env::var_os("XDG_CONFIG_HOME") {
+            Some(folder) if !folder.is_empty() => Some(PathBuf::from(folder)),
+            _ => env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")),
+        };
+
+        if let Some(config_home) = config_home {
+            paths.push(config_home.join("aoc_rust_2021").join(FILE_NAME));
+        }
+
+        paths
+    }
+
+    /// Settings of the first configuration file found, the defaults otherwise.
+    /// Setting `AOC_CACHE` enables the cache.
+    pub fn load() -> Result<Self, String> {
+        let mut config = Config::default();
+
+        for path in Self::candidate_paths() {
+            match fs::read_to_string(&path) {
+                Ok(text) => {
+                    config = text
+                        .parse()
+                        .map_err(|error| format!("{}: {}", path.display(), error))?;
+                    config.source = Some(path);
+                    break;
+                }
+                Err(error) if error.kind() == ErrorKind::NotFound => continue,
+                Err(error) => return Err(format!("{}: {}", path.display(), error)),
+            }
+        }
+
+        if env::var_os("AOC_CACHE").is_some_and(|value| !value.is_empty()) {
+            config.cache = true;
+        }
+
+        Ok(config)
+    }
+
+    /// Apply the flag at `args[*index]`, moving the index past it and its value.
+    /// Returns `false`, without moving, when it is not a configuration flag.
+    pub fn apply_flag(&mut self, args: &[String], index: &mut usize) -> Result<bool, String> {
+        match args[*index].as_str() {
+            "--input-dir" => self.input_dir = Some(option_value(args, index)?.to_string()),
+            "--format" => self.format = option_value(args, index)?.parse()?,
+            "--iterations" => self.iterations = parse_iterations(option_value(args, index)?)?,
+            "--days" => self.days = Some(parse_days_list(option_value(args, index)?)?),
+            "--answers" => self.answers = Some(option_value(args, index)?.to_string()),
+            "--color" => self.color = option_value(args, index)?.parse()?,
+            "--cache" => self.cache = true,
+            "--no-cache" => self.cache = false,
+            _ => return Ok(false),
+        }
+        *index += 1;
+
+        Ok(true)
+    }
+
+    /// Effective settings, in the format of `aoc.toml`.
+    pub fn show(&self) -> Vec<String> {
+        let days = self
+            .days
+            .clone()
+            .unwrap_or_else(|| days().iter().map(|day| day.day).collect());
+
+        vec![
+            match &self.source {
+                Some(path) => format!("# Read from {}", path.display()),
+                None => String::from("# No configuration file, using the defaults"),
+            },
+            match &self.input_dir {
+                Some(input_dir) => format!("input_dir = {}", quote(input_dir)),
+                None => String::from("# input_dir is not set"),
+            },
+            format!(
+                "format = \"{}\"",
+                match self.format {
+                    Format::Text => "text",
+                    Format::Json => "json",
+                }
+            ),
+            format!("iterations = {}", self.iterations),
+            format!(
+                "days = [{}]",
+                days.iter()
+                    .map(u8::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            match &self.answers {
+                Some(answers) => format!("answers = {}", quote(answers)),
+                None => String::from("# answers is not set"),
+            },
+            format!(
+                "color = \"{}\"",
+                match self.color {
+                    Color::Auto => "auto",
+                    Color::Always => "always",
+                    Color::Never => "never",
+                }
+            ),
+            format!("cache = {}", self.cache),
+        ]
+    }
+}
+
+pub fn main(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "usage: aoc_rust_2021 config show [flags]";
+
+    match args.split_first() {
+        Some((command, flags)) if command == "show" => {
+            let mut config = Config::load()?;
+            let mut index = 0;
+
+            while index < flags.len() {
+                if !config.apply_flag(flags, &mut index)? {
+                    return Err(String::from(USAGE));
+                }
+            }
+
+            for line in config.show() {
+                println!("{}", line);
+            }
+
+            Ok(())
+        }
+        _ => Err(String::from(USAGE)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::config::{parse_days_list, parse_string, strip_comment, Color, Config, Format};
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = "# Runner defaults
+input_dir = \"inputs/alice\"  # my inputs
+format = \"json\"
+iterations = 10
+days = [1, 3, 11]
+answers = \"answers.txt\"
+color = \"never\"
+cache = true
+"
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                input_dir: Some(String::from("inputs/alice")),
+                format: Format::Json,
+                iterations: 10,
+                days: Some(vec![1, 3, 11]),
+                answers: Some(String::from("answers.txt")),
+                color: Color::Never,
+                cache: true,
+                source: None,
+            }
+        );
+        assert_eq!("".parse(), Ok(Config::default()));
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        assert_eq!(
+            "format = \"json\"\ncolour = \"never\"".parse::<Config>(),
+            Err(String::from("line 2: unknown setting: colour"))
+        );
+        assert_eq!(
+            "iterations = 0".parse::<Config>(),
+            Err(String::from(
+                "line 1: iterations must be a positive integer, found 0"
+            ))
+        );
+        assert!("input_dir = inputs".parse::<Config>().is_err());
+        assert!("cache".parse::<Config>().is_err());
+    }
+
+    #[test]
+    fn test_strings() {
+        assert_eq!(strip_comment("a = \"#1\" # comment"), "a = \"#1\" ");
+        assert_eq!(
+            parse_string("\"C:\\\\aoc \\\"2021\\\"\""),
+            Ok(String::from("C:\\aoc \"2021\""))
+        );
+    }
+
+    #[test]
+    fn test_parse_days_list() {
+        assert_eq!(parse_days_list("1,3-5,11"), Ok(vec![1, 3, 4, 5, 11]));
+        assert!(parse_days_list("1,x").is_err());
+    }
+
+    #[test]
+    fn test_apply_flag() {
+        let args: Vec<String> = ["--format", "json", "--no-cache", "src/input"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let mut config = Config {
+            cache: true,
+            ..Config::default()
+        };
+        let mut index = 0;
+
+        assert_eq!(config.apply_flag(&args, &mut index), Ok(true));
+        assert_eq!(index, 2);
+        assert_eq!(config.apply_flag(&args, &mut index), Ok(true));
+        assert_eq!(config.apply_flag(&args, &mut index), Ok(false));
+        assert_eq!(index, 3);
+        assert_eq!(config.format, Format::Json);
+        assert!(!config.cache);
+    }
+}
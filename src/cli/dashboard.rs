@@ -0,0 +1,302 @@
+use crate::cli::inputs::Inputs;
+use crate::cli::{panic_message, truncate};
+use aoc_rust_2021::{days, AocError};
+use std::io::{self, IsTerminal, Write};
+use std::panic;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const BAR_WIDTH: usize = 20;
+const ANSWER_WIDTH: usize = 28;
+
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const CLEAR_LINE: &str = "\x1b[2K";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Status {
+    Pending,
+    Running,
+    Pass { answer: String, time: Duration },
+    Fail { error: String, time: Duration },
+}
+
+struct Row {
+    day: u8,
+    part: u8,
+    title: &'static str,
+    status: Status,
+}
+
+enum Event {
+    Started(usize),
+    Finished(usize, Status),
+}
+
+fn bar(time: Duration, slowest: Duration) -> String {
+    let filled = if slowest.is_zero() {
+        0
+    } else {
+        ((time.as_secs_f64() / slowest.as_secs_f64()) * BAR_WIDTH as f64).ceil() as usize
+    };
+
+    format!(
+        "{}{}",
+        "█".repeat(filled.min(BAR_WIDTH)),
+        "·".repeat(BAR_WIDTH - filled.min(BAR_WIDTH))
+    )
+}
+
+fn render(rows: &[Row], colour: bool) -> Vec<String> {
+    let paint = |code: &str, text: String| {
+        if colour {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            text
+        }
+    };
+    let slowest = rows
+        .iter()
+        .filter_map(|row| match &row.status {
+            Status::Pass { time, .. } | Status::Fail { time, .. } => Some(*time),
+            _ => None,
+        })
+        .max()
+        .unwrap_or_default();
+
+    let mut lines = vec![format!(
+        "{:>3} {:>4}  {:<24} {:<7}  {:<width$} {:>10}  {}",
+        "Day",
+        "Part",
+        "Title",
+        "Status",
+        "Answer",
+        "Time",
+        "Relative time",
+        width = ANSWER_WIDTH
+    )];
+
+    for row in rows {
+        let (status, answer, time) = match &row.status {
+            Status::Pending => (paint(DIM, format!("{:<7}", "pending")), "", None),
+            Status::Running => (paint(YELLOW, format!("{:<7}", "running")), "", None),
+            Status::Pass { answer, time } => (
+                paint(GREEN, format!("{:<7}", "pass")),
+                answer.as_str(),
+                Some(*time),
+            ),
+            Status::Fail { error, time } => (
+                paint(RED, format!("{:<7}", "fail")),
+                error.as_str(),
+                Some(*time),
+            ),
+        };
+
+        lines.push(format!(
+            "{:>3} {:>4}  {:<24} {}  {:<width$} {:>10}  {}",
+            row.day,
+            row.part,
+            truncate(row.title, 24),
+            status,
+            truncate(answer, ANSWER_WIDTH),
+            time.map(|time| format!("{}μs", time.as_micros()))
+                .unwrap_or_default(),
+            time.map(|time| bar(time, slowest)).unwrap_or_default(),
+            width = ANSWER_WIDTH
+        ));
+    }
+
+    lines
+}
+
+fn draw(output: &mut impl Write, lines: &[String], redraw: bool) -> io::Result<()> {
+    if redraw {
+        // Move back to the first line of the previous drawing.
+        write!(output, "\x1b[{}F", lines.len())?;
+    }
+    for line in lines {
+        writeln!(output, "{}{}", if redraw { CLEAR_LINE } else { "" }, line)?;
+    }
+
+    output.flush()
+}
+
+fn solve_all(inputs: Inputs, events: mpsc::Sender<Event>) {
+    let mut index = 0;
+
+    for day in days() {
+        let input = inputs.load(day.day);
+
+        for part in 1..=2 {
+            events.send(Event::Started(index)).ok();
+
+            let chrono_start = Instant::now();
+            let solution_result = match &input {
+                Ok(input) => panic::catch_unwind(|| day.solve(part, input))
+                    .unwrap_or_else(|payload| Err(AocError::Solver(panic_message(payload))))
+                    .map_err(|error| error.to_string()),
+                Err(error) => Err(error.clone()),
+            };
+            let time = chrono_start.elapsed();
+
+            let status = match solution_result {
+                Ok(answer) => Status::Pass {
+                    answer: answer.to_string(),
+                    time,
+                },
+                Err(error) => Status::Fail { error, time },
+            };
+            events.send(Event::Finished(index, status)).ok();
+
+            index += 1;
+        }
+    }
+}
+
+type PanicHook = Box<dyn Fn(&panic::PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+/// Hides panic messages while alive, and restores the previous panic hook when
+/// dropped, even if the dashboard returns early with an error.
+struct SilencedPanics {
+    default_hook: Option<PanicHook>,
+}
+
+impl SilencedPanics {
+    fn new() -> SilencedPanics {
+        let default_hook = Some(panic::take_hook());
+        panic::set_hook(Box::new(|_| {}));
+
+        SilencedPanics { default_hook }
+    }
+}
+
+impl Drop for SilencedPanics {
+    fn drop(&mut self) {
+        // The hook cannot be changed by a panicking thread.
+        if let Some(default_hook) = self.default_hook.take().filter(|_| !thread::panicking()) {
+            panic::set_hook(default_hook);
+        }
+    }
+}
+
+pub fn main(args: &[String]) -> Result<(), String> {
+    let inputs = match args {
+        [] => Inputs::new(None)?,
+        [folder] => Inputs::new(Some(folder))?,
+        _ => {
+            return Err(String::from(
+                "usage: aoc_rust_2021 dashboard [input folder]",
+            ))
+        }
+    };
+    let mut rows: Vec<Row> = days()
+        .iter()
+        .flat_map(|day| {
+            (1..=2).map(move |part| Row {
+                day: day.day,
+                part,
+                title: day.title,
+                status: Status::Pending,
+            })
+        })
+        .collect();
+
+    let mut stdout = io::stdout();
+    let live = stdout.is_terminal();
+    let write_error = |error: io::Error| error.to_string();
+
+    // Panic messages would be printed in the middle of the table, they are
+    // shown in the answer column instead.
+    let silenced_panics = SilencedPanics::new();
+
+    let (sender, receiver) = mpsc::channel();
+    let worker = thread::spawn(move || solve_all(inputs, sender));
+
+    if live {
+        draw(&mut stdout, &render(&rows, true), false).map_err(write_error)?;
+    }
+    for event in receiver {
+        match event {
+            Event::Started(index) => rows[index].status = Status::Running,
+            Event::Finished(index, status) => rows[index].status = status,
+        }
+        if live {
+            draw(&mut stdout, &render(&rows, true), true).map_err(write_error)?;
+        }
+    }
+
+    worker.join().ok();
+    drop(silenced_panics);
+
+    if !live {
+        draw(&mut stdout, &render(&rows, false), false).map_err(write_error)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::dashboard::{bar, render, Row, Status};
+    use std::time::Duration;
+
+    #[test]
+    fn test_bar() {
+        assert_eq!(
+            bar(Duration::from_micros(50), Duration::from_micros(100)),
+            "██████████··········"
+        );
+        assert_eq!(bar(Duration::ZERO, Duration::ZERO), "····················");
+    }
+
+    #[test]
+    fn test_render() {
+        let rows = vec![
+            Row {
+                day: 1,
+                part: 1,
+                title: "Sonar Sweep",
+                status: Status::Pass {
+                    answer: String::from("7"),
+                    time: Duration::from_micros(10),
+                },
+            },
+            Row {
+                day: 1,
+                part: 2,
+                title: "Sonar Sweep",
+                status: Status::Fail {
+                    error: String::from("boom"),
+                    time: Duration::from_micros(20),
+                },
+            },
+            Row {
+                day: 2,
+                part: 1,
+                title: "Dive!",
+                status: Status::Running,
+            },
+        ];
+
+        let lines = render(&rows, false);
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[1],
+            "  1    1  Sonar Sweep              pass     7                                  10μs  ██████████··········"
+        );
+        assert_eq!(
+            lines[2],
+            "  1    2  Sonar Sweep              fail     boom                               20μs  ████████████████████"
+        );
+        assert_eq!(
+            lines[3].trim_end(),
+            "  2    1  Dive!                    running"
+        );
+        assert!(render(&rows, true)[1].contains("\x1b[32mpass   \x1b[0m"));
+    }
+}
@@ -0,0 +1,41 @@
+use crate::cli::option_value;
+use aoc_rust_2021::generate;
+
+const USAGE: &str = "usage: aoc_rust_2021 generate --day N [--size S] [--seed X]";
+
+pub fn main(args: &[String]) -> Result<(), String> {
+    let mut day = None;
+    let mut size = 100;
+    let mut seed = 0;
+    let mut index = 0;
+
+    while index < args.len() {
+        let option = args[index].as_str();
+        let invalid = |value: &str| format!("invalid value for {}: {}", option, value);
+
+        match option {
+            "--day" => {
+                let value = option_value(args, &mut index)?;
+                day = Some(value.parse().map_err(|_| invalid(value))?);
+            }
+            "--size" => {
+                let value = option_value(args, &mut index)?;
+                size = value.parse().map_err(|_| invalid(value))?;
+            }
+            "--seed" => {
+                let value = option_value(args, &mut index)?;
+                seed = value.parse().map_err(|_| invalid(value))?;
+            }
+            _ => return Err(String::from(USAGE)),
+        }
+        index += 1;
+    }
+
+    let day = day.ok_or(USAGE)?;
+    print!(
+        "{}",
+        generate(day, size, seed).map_err(|error| error.to_string())?
+    );
+
+    Ok(())
+}
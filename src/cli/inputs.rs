@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::PathBuf;
+
+#[cfg(feature = "embed-inputs")]
+static EMBEDDED_INPUTS: &[(u8, &str)] = &[
+    #[cfg(feature = "day1")]
+    (1, include_str!("../input/day1.txt")),
+    #[cfg(feature = "day2")]
+    (2, include_str!("../input/day2.txt")),
+    #[cfg(feature = "day3")]
+    (3, include_str!("../input/day3.txt")),
+    #[cfg(feature = "day4")]
+    (4, include_str!("../input/day4.txt")),
+    #[cfg(feature = "day5")]
+    (5, include_str!("../input/day5.txt")),
+    #[cfg(feature = "day6")]
+    (6, include_str!("../input/day6.txt")),
+    #[cfg(feature = "day7")]
+    (7, include_str!("../input/day7.txt")),
+    #[cfg(feature = "day8")]
+    (8, include_str!("../input/day8.txt")),
+    #[cfg(feature = "day9")]
+    (9, include_str!("../input/day9.txt")),
+    #[cfg(feature = "day10")]
+    (10, include_str!("../input/day10.txt")),
+    #[cfg(feature = "day11")]
+    (11, include_str!("../input/day11.txt")),
+];
+
+/// Where the puzzle inputs are read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inputs {
+    /// Folder with one `dayN.txt` file per day.
+    Folder(PathBuf),
+    /// The inputs of src/input, included in the binary at build time.
+    #[cfg(feature = "embed-inputs")]
+    Embedded,
+}
+
+impl Inputs {
+    /// Inputs from `folder`, or the embedded inputs when no folder is given
+    /// and the binary was built with the `embed-inputs` feature.
+    pub fn new(folder: Option<&str>) -> Result<Self, String> {
+        match folder {
+            Some(folder) => Ok(Inputs::Folder(PathBuf::from(folder))),
+            #[cfg(feature = "embed-inputs")]
+            None => Ok(Inputs::Embedded),
+            #[cfg(not(feature = "embed-inputs"))]
+            None => Err(String::from(
+                "missing input folder (build with the embed-inputs feature to include the inputs)",
+            )),
+        }
+    }
+
+    pub fn load(&self, day: u8) -> Result<String, String> {
+        match self {
+            Inputs::Folder(folder) => {
+                let path = folder.join(format!("day{}.txt", day));
+
+                fs::read_to_string(&path).map_err(|error| format!("{}: {}", path.display(), error))
+            }
+            #[cfg(feature = "embed-inputs")]
+            Inputs::Embedded => EMBEDDED_INPUTS
+                .iter()
+                .find(|(embedded_day, _)| *embedded_day == day)
+                .map(|(_, input)| input.to_string())
+                .ok_or_else(|| format!("no input embedded for day {}", day)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::inputs::Inputs;
+    #[cfg(feature = "embed-inputs")]
+    use aoc_rust_2021::days;
+    use std::path::PathBuf;
+
+    #[cfg(feature = "day6")]
+    #[test]
+    fn test_load_from_folder() {
+        let inputs = Inputs::new(Some(concat!(env!("CARGO_MANIFEST_DIR"), "/src/input"))).unwrap();
+
+        assert!(inputs
+            .load(6)
+            .unwrap()
+            .starts_with("1,1,1,2,1,1,2,1,1,1,5,"));
+        assert!(inputs
+            .load(42)
+            .unwrap_err()
+            .ends_with("day42.txt: No such file or directory (os error 2)"));
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            Inputs::new(Some("inputs")),
+            Ok(Inputs::Folder(PathBuf::from("inputs")))
+        );
+        #[cfg(not(feature = "embed-inputs"))]
+        assert!(Inputs::new(None).is_err());
+    }
+
+    #[cfg(feature = "embed-inputs")]
+    #[test]
+    fn test_load_embedded() {
+        let folder = Inputs::new(Some(concat!(env!("CARGO_MANIFEST_DIR"), "/src/input"))).unwrap();
+        let embedded = Inputs::new(None).unwrap();
+
+        for day in days() {
+            assert_eq!(embedded.load(day.day), folder.load(day.day));
+        }
+        assert!(embedded.load(12).is_err());
+    }
+}
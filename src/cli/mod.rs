@@ -0,0 +1,56 @@
+pub mod batch;
+pub mod cache;
+pub mod config;
+pub mod dashboard;
+pub mod generate;
+pub mod inputs;
+pub mod json;
+pub mod repl;
+pub mod run;
+pub mod serve;
+
+use std::any::Any;
+
+/// Value of the option at `args[*index]`, moving the index past it.
+pub fn option_value<'a>(args: &'a [String], index: &mut usize) -> Result<&'a str, String> {
+    let option = &args[*index];
+    *index += 1;
+
+    args.get(*index)
+        .map(String::as_str)
+        .ok_or_else(|| format!("missing value for {}", option))
+}
+
+/// Message of a panic caught with `catch_unwind`.
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => format!("panicked: {}", message),
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => format!("panicked: {}", message),
+            Err(_) => String::from("panicked"),
+        },
+    }
+}
+
+/// First line of `text`, shortened to `width` characters.
+pub fn truncate(text: &str, width: usize) -> String {
+    let text = text.lines().next().unwrap_or("");
+
+    if text.chars().count() > width {
+        format!("{}…", text.chars().take(width - 1).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::truncate;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("much too long", 10), "much too …");
+        assert_eq!(truncate("first\nsecond", 10), "first");
+    }
+}
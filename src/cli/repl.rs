@@ -0,0 +1,386 @@
+use crate::cli::inputs::Inputs;
+use crate::cli::panic_message;
+#[cfg(feature = "day11")]
+use aoc_rust_2021::day11;
+#[cfg(feature = "day9")]
+use aoc_rust_2021::day9;
+use aoc_rust_2021::{days, find_day, AocError, Day};
+use std::io::{self, BufRead, Write};
+use std::panic;
+use std::time::Instant;
+
+const DEFAULT_INPUT_FOLDER: &str = "src/input";
+
+const HELP: &str = "Commands:
+  days            list the available days
+  load <day>      load the input of a day from the input folder
+  example [day]   load the example from the puzzle description
+  reload          read the current input again
+  run [part]      run one part, or both, parsing the loaded input again
+  time            toggle the display of solving times
+  show            describe the loaded input
+  show input      print the loaded input
+  show grid       print the loaded input as a grid (days 9 and 11)
+  help            show this message
+  quit            leave the REPL";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    InputFolder,
+    Example,
+}
+
+struct Loaded {
+    day: &'static Day,
+    source: Source,
+    input: String,
+}
+
+struct Session {
+    inputs: Inputs,
+    loaded: Option<Loaded>,
+    timing: bool,
+}
+
+enum Control {
+    Continue,
+    Quit,
+}
+
+fn parse_day(argument: &str) -> Result<&'static Day, String> {
+    let day = argument
+        .parse::<u8>()
+        .map_err(|_| format!("invalid day: {}", argument))?;
+
+    find_day(day).map_err(|error| error.to_string())
+}
+
+#[cfg(any(feature = "day9", feature = "day11"))]
+fn write_grid<T: ToString>(output: &mut impl Write, grid: &[Vec<T>]) -> io::Result<()> {
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    let ruler = (0..width)
+        .map(|x| char::from_digit((x % 10) as u32, 10).unwrap())
+        .collect::<String>();
+
+    writeln!(output, "{}x{} grid", width, grid.len())?;
+    writeln!(output, "     {}", ruler)?;
+    for (y, row) in grid.iter().enumerate() {
+        let row = row.iter().map(ToString::to_string).collect::<String>();
+        writeln!(output, "{:>4} {}", y, row)?;
+    }
+
+    Ok(())
+}
+
+impl Session {
+    fn new(inputs: Inputs) -> Self {
+        Self {
+            inputs,
+            loaded: None,
+            timing: false,
+        }
+    }
+
+    fn loaded(&self) -> Result<&Loaded, String> {
+        self.loaded
+            .as_ref()
+            .ok_or_else(|| String::from("nothing loaded, use `load <day>` or `example <day>`"))
+    }
+
+    fn load(&mut self, day: &'static Day, source: Source) -> Result<String, String> {
+        let input = match source {
+            Source::InputFolder => self.inputs.load(day.day)?,
+            Source::Example => day.example.to_string(),
+        };
+        let description = format!(
+            "Loaded day {} ({}) from {}: {} lines",
+            day.day,
+            day.title,
+            match source {
+                Source::InputFolder => "the input folder",
+                Source::Example => "the puzzle example",
+            },
+            input.lines().count()
+        );
+
+        self.loaded = Some(Loaded { day, source, input });
+
+        Ok(description)
+    }
+
+    fn run(&self, output: &mut impl Write, part: u8) -> Result<(), String> {
+        let loaded = self.loaded()?;
+
+        let chrono_start = Instant::now();
+        let solution_result = panic::catch_unwind(|| loaded.day.solve(part, &loaded.input))
+            .unwrap_or_else(|payload| Err(AocError::Solver(panic_message(payload))));
+        let chrono_stop = chrono_start.elapsed().as_micros();
+
+        let solution = solution_result.map_err(|error| error.to_string())?;
+        if self.timing {
+            writeln!(output, "Part {}: {} ({}μs)", part, solution, chrono_stop)
+        } else {
+            writeln!(output, "Part {}: {}", part, solution)
+        }
+        .map_err(|error| error.to_string())
+    }
+
+    fn show(&self, output: &mut impl Write, what: Option<&str>) -> Result<(), String> {
+        let loaded = self.loaded()?;
+
+        match what {
+            None => writeln!(
+                output,
+                "Day {} ({}), {} input, {} lines, {} bytes",
+                loaded.day.day,
+                loaded.day.title,
+                match loaded.source {
+                    Source::InputFolder => "puzzle",
+                    Source::Example => "example",
+                },
+                loaded.input.lines().count(),
+                loaded.input.len()
+            ),
+            Some("input") => write!(output, "{}", loaded.input),
+            Some("grid") => match loaded.day.day {
+                #[cfg(feature = "day9")]
+                9 => write_grid(output, &day9::parse_input(&loaded.input)),
+                #[cfg(feature = "day11")]
+                11 => write_grid(output, &day11::parse_input(&loaded.input)?),
+                day => return Err(format!("the input of day {} is not a grid", day)),
+            },
+            Some(other) => return Err(format!("cannot show {}", other)),
+        }
+        .map_err(|error| error.to_string())
+    }
+
+    fn execute(&mut self, line: &str, output: &mut impl Write) -> Result<Control, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let write_error = |error: io::Error| error.to_string();
+
+        match words.as_slice() {
+            [] => {}
+            ["help"] => writeln!(output, "{}", HELP).map_err(write_error)?,
+            ["quit" | "exit"] => return Ok(Control::Quit),
+            ["days"] => {
+                for day in days() {
+                    writeln!(output, "{:>2} {}", day.day, day.title).map_err(write_error)?;
+                }
+            }
+            ["load"] => return Err(String::from("missing day number")),
+            ["example"] if self.loaded.is_none() => return Err(String::from("missing day number")),
+            ["load", day] => {
+                let description = self.load(parse_day(day)?, Source::InputFolder)?;
+                writeln!(output, "{}", description).map_err(write_error)?;
+            }
+            ["example"] => {
+                let description = self.load(self.loaded()?.day, Source::Example)?;
+                writeln!(output, "{}", description).map_err(write_error)?;
+            }
+            ["example", day] => {
+                let description = self.load(parse_day(day)?, Source::Example)?;
+                writeln!(output, "{}", description).map_err(write_error)?;
+            }
+            ["reload"] => {
+                let loaded = self.loaded()?;
+                let description = self.load(loaded.day, loaded.source)?;
+                writeln!(output, "{}", description).map_err(write_error)?;
+            }
+            ["run"] => {
+                self.run(output, 1)?;
+                self.run(output, 2)?;
+            }
+            ["run", part] => {
+                let part = part
+                    .parse::<u8>()
+                    .map_err(|_| format!("invalid part: {}", part))?;
+                self.run(output, part)?;
+            }
+            ["time"] => {
+                self.timing = !self.timing;
+                writeln!(
+                    output,
+                    "Timing is {}",
+                    if self.timing { "on" } else { "off" }
+                )
+                .map_err(write_error)?;
+            }
+            ["show"] => self.show(output, None)?,
+            ["show", what] => self.show(output, Some(what))?,
+            [command, ..] => {
+                return Err(format!(
+                    "unknown command or arguments: {} (try `help`)",
+                    command
+                ))
+            }
+        }
+
+        Ok(Control::Continue)
+    }
+}
+
+pub fn main(args: &[String]) -> Result<(), String> {
+    let inputs = match args {
+        [] if cfg!(feature = "embed-inputs") => Inputs::new(None)?,
+        [] => Inputs::new(Some(DEFAULT_INPUT_FOLDER))?,
+        [folder] => Inputs::new(Some(folder))?,
+        _ => return Err(String::from("usage: aoc_rust_2021 repl [input folder]")),
+    };
+    let mut session = Session::new(inputs);
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    loop {
+        print!("aoc> ");
+        stdout.flush().map_err(|error| error.to_string())?;
+
+        let mut line = String::new();
+        if stdin
+            .lock()
+            .read_line(&mut line)
+            .map_err(|error| error.to_string())?
+            == 0
+        {
+            println!();
+            return Ok(());
+        }
+
+        match session.execute(&line, &mut stdout) {
+            Ok(Control::Continue) => {}
+            Ok(Control::Quit) => return Ok(()),
+            Err(error) => println!("Error: {}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::inputs::Inputs;
+    use crate::cli::repl::{Control, Session};
+
+    fn execute(session: &mut Session, line: &str) -> Result<String, String> {
+        let mut output: Vec<u8> = Vec::new();
+
+        session.execute(line, &mut output)?;
+
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    fn session() -> Session {
+        Session::new(Inputs::new(Some(concat!(env!("CARGO_MANIFEST_DIR"), "/src/input"))).unwrap())
+    }
+
+    #[cfg(feature = "day9")]
+    #[test]
+    fn test_example_and_run() {
+        let mut session = session();
+
+        assert_eq!(
+            execute(&mut session, "example 9"),
+            Ok(String::from(
+                "Loaded day 9 (Smoke Basin) from the puzzle example: 5 lines\n"
+            ))
+        );
+        assert_eq!(
+            execute(&mut session, "run"),
+            Ok(String::from("Part 1: 15\nPart 2: 1134\n"))
+        );
+        assert_eq!(
+            execute(&mut session, "run 2"),
+            Ok(String::from("Part 2: 1134\n"))
+        );
+    }
+
+    #[cfg(feature = "day1")]
+    #[test]
+    fn test_load_and_reload() {
+        let mut session = session();
+
+        assert_eq!(
+            execute(&mut session, "load 1"),
+            Ok(String::from(
+                "Loaded day 1 (Sonar Sweep) from the input folder: 2000 lines\n"
+            ))
+        );
+        assert_eq!(
+            execute(&mut session, "run 1"),
+            Ok(String::from("Part 1: 1316\n"))
+        );
+        assert_eq!(
+            execute(&mut session, "example"),
+            Ok(String::from(
+                "Loaded day 1 (Sonar Sweep) from the puzzle example: 10 lines\n"
+            ))
+        );
+        assert_eq!(
+            execute(&mut session, "reload"),
+            Ok(String::from(
+                "Loaded day 1 (Sonar Sweep) from the puzzle example: 10 lines\n"
+            ))
+        );
+    }
+
+    #[cfg(feature = "day7")]
+    #[test]
+    fn test_time() {
+        let mut session = session();
+
+        execute(&mut session, "example 7").unwrap();
+
+        assert_eq!(
+            execute(&mut session, "time"),
+            Ok(String::from("Timing is on\n"))
+        );
+        let output = execute(&mut session, "run 1").unwrap();
+        assert!(output.starts_with("Part 1: 37 (") && output.ends_with("μs)\n"));
+    }
+
+    #[cfg(all(feature = "day1", feature = "day11"))]
+    #[test]
+    fn test_show() {
+        let mut session = session();
+
+        execute(&mut session, "example 11").unwrap();
+
+        assert_eq!(
+            execute(&mut session, "show"),
+            Ok(String::from(
+                "Day 11 (Dumbo Octopus), example input, 10 lines, 110 bytes\n"
+            ))
+        );
+        assert!(execute(&mut session, "show grid")
+            .unwrap()
+            .starts_with("10x10 grid\n     0123456789\n   0 5483143223\n"));
+
+        execute(&mut session, "example 1").unwrap();
+        assert_eq!(
+            execute(&mut session, "show grid"),
+            Err(String::from("the input of day 1 is not a grid"))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut session = session();
+
+        assert_eq!(
+            execute(&mut session, "run"),
+            Err(String::from(
+                "nothing loaded, use `load <day>` or `example <day>`"
+            ))
+        );
+        assert_eq!(
+            execute(&mut session, "load 30"),
+            Err(String::from("no solver available for day 30"))
+        );
+        assert_eq!(
+            execute(&mut session, "load"),
+            Err(String::from("missing day number"))
+        );
+        assert!(execute(&mut session, "frobnicate").is_err());
+        assert!(matches!(
+            session.execute("quit", &mut Vec::new()),
+            Ok(Control::Quit)
+        ));
+    }
+}
@@ -0,0 +1,316 @@
+use crate::cli::cache::Cache;
+use crate::cli::config::{Color, Config, Format};
+use crate::cli::inputs::Inputs;
+use crate::cli::json;
+use aoc_rust_2021::{days, find_day, Day};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::time::Instant;
+
+const USAGE: &str =
+    "usage: aoc_rust_2021 [run] [--explain] [--input-dir DIR] [--format text|json] \
+[--iterations N] [--days LIST] [--answers FILE] [--color auto|always|never] [--cache | --no-cache] \
+[input folder]";
+
+const RESET: &str = "\x1b[0m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+
+/// Result of one part of one day.
+struct Outcome {
+    day: u8,
+    part: u8,
+    solution: Result<String, String>,
+    /// Average time of the iterations, in μs.
+    time: u128,
+    cached: bool,
+    expected: Option<String>,
+    explanation: Option<Vec<String>>,
+}
+
+/// Expected answers of a file with one `day part answer` per line.
+fn load_answers(path: &str) -> Result<HashMap<(u8, u8), String>, String> {
+    let text = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+    let mut answers = HashMap::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.splitn(3, char::is_whitespace).collect();
+        match fields.as_slice() {
+            [day, part, answer] => match (day.parse(), part.parse()) {
+                (Ok(day), Ok(part)) => answers.insert((day, part), answer.trim().to_string()),
+                _ => return Err(format!("{}: line {}: invalid day or part", path, index + 1)),
+            },
+            _ => {
+                return Err(format!(
+                    "{}: line {}: expected day, part and answer",
+                    path,
+                    index + 1
+                ))
+            }
+        };
+    }
+
+    Ok(answers)
+}
+
+fn selected_days(config: &Config) -> Result<Vec<&'static Day>, String> {
+    match &config.days {
+        Some(selected) => selected
+            .iter()
+            .map(|&day| find_day(day).map_err(|error| error.to_string()))
+            .collect(),
+        None => Ok(days().iter().collect()),
+    }
+}
+
+fn solve(day: &Day, part: u8, input: &str, config: &Config, cache: Option<&Cache>) -> Outcome {
+    let cached = cache.and_then(|cache| cache.get(day.day, part, input));
+    let (solution, time) = match &cached {
+        Some(answer) => (Ok(answer.clone()), 0),
+        None => {
+            let mut solution = Err(String::new());
+            let chrono_start = Instant::now();
+
+            for _ in 0..config.iterations {
+                solution = day
+                    .solve(part, input)
+                    .map(|answer| answer.to_string())
+                    .map_err(|error| error.to_string());
+            }
+
+            (
+                solution,
+                chrono_start.elapsed().as_micros() / u128::from(config.iterations),
+            )
+        }
+    };
+
+    if let (Some(cache), None, Ok(solution)) = (cache, &cached, &solution) {
+        if let Err(error) = cache.put(day.day, part, input, solution) {
+            eprintln!("Could not cache the answer: {}", error);
+        }
+    }
+
+    Outcome {
+        day: day.day,
+        part,
+        solution,
+        time,
+        cached: cached.is_some(),
+        expected: None,
+        explanation: None,
+    }
+}
+
+fn print_text(outcome: &Outcome, colour: bool) {
+    let paint = |code: &str, text: String| {
+        if colour {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            text
+        }
+    };
+
+    match &outcome.solution {
+        Ok(solution) => {
+            let check = match &outcome.expected {
+                Some(expected) if expected == solution => paint(GREEN, String::from(" ✓")),
+                Some(expected) => paint(RED, format!(" ✗ (expected {})", expected)),
+                None => String::new(),
+            };
+
+            println!(
+                "Solution of Day {}, Part {}: {}, Time: {}μs{}{}",
+                outcome.day,
+                outcome.part,
+                solution,
+                outcome.time,
+                if outcome.cached { " (cached)" } else { "" },
+                check
+            )
+        }
+        Err(error) => println!(
+            "{}",
+            paint(
+                RED,
+                format!(
+                    "A problem occured to solve the problem of Day {}, Part {}: {}, Time: {}μs",
+                    outcome.day, outcome.part, error, outcome.time
+                )
+            )
+        ),
+    }
+
+    for line in outcome.explanation.iter().flatten() {
+        println!("    {}", line);
+    }
+}
+
+fn to_json(outcome: &Outcome) -> String {
+    let mut fields = vec![
+        format!("\"day\":{}", outcome.day),
+        format!("\"part\":{}", outcome.part),
+    ];
+
+    match &outcome.solution {
+        Ok(solution) => {
+            fields.push(format!("\"answer\":{}", json::string(solution)));
+            if let Some(expected) = &outcome.expected {
+                fields.push(format!("\"expected\":{}", json::string(expected)));
+                fields.push(format!("\"correct\":{}", expected == solution));
+            }
+        }
+        Err(error) => fields.push(format!("\"error\":{}", json::string(error))),
+    }
+    fields.push(format!("\"time_us\":{}", outcome.time));
+    fields.push(format!("\"cached\":{}", outcome.cached));
+    if let Some(explanation) = &outcome.explanation {
+        fields.push(format!(
+            "\"explanation\":[{}]",
+            explanation
+                .iter()
+                .map(|line| json::string(line))
+                .collect::<Vec<String>>()
+                .join(",")
+        ));
+    }
+
+    format!("{{{}}}", fields.join(","))
+}
+
+pub fn main(args: &[String]) -> Result<(), String> {
+    let mut config = Config::load()?;
+    let mut explain = false;
+    let mut folder = None;
+    let mut index = 0;
+
+    while index < args.len() {
+        if config.apply_flag(args, &mut index)? {
+            continue;
+        }
+
+        match args[index].as_str() {
+            "--explain" => explain = true,
+            arg if folder.is_none() && !arg.starts_with('-') => folder = Some(arg.to_string()),
+            _ => return Err(String::from(USAGE)),
+        }
+        index += 1;
+    }
+
+    // A positional input folder takes precedence over the configured one.
+    let inputs = Inputs::new(folder.or(config.input_dir.clone()).as_deref())?;
+    let cache = if config.cache {
+        Some(Cache::new(Cache::default_folder()?))
+    } else {
+        None
+    };
+    let answers = match &config.answers {
+        Some(path) => load_answers(path)?,
+        None => HashMap::new(),
+    };
+    let colour = match config.color {
+        Color::Auto => io::stdout().is_terminal(),
+        Color::Always => true,
+        Color::Never => false,
+    };
+    let mut outcomes = Vec::new();
+    let mut total_time: u128 = 0;
+
+    for day in selected_days(&config)? {
+        let input = inputs.load(day.day)?;
+
+        for part in 1..=2 {
+            let mut outcome = solve(day, part, &input, &config, cache.as_ref());
+            total_time += outcome.time;
+
+            outcome.expected = answers.get(&(day.day, part)).cloned();
+            // Explanations are not timed, they solve the puzzle again.
+            if explain {
+                outcome.explanation = Some(
+                    day.explain(part, &input)
+                        .unwrap_or_else(|error| vec![format!("No explanation: {}", error)]),
+                );
+            }
+
+            match config.format {
+                Format::Text => print_text(&outcome, colour),
+                Format::Json => outcomes.push(outcome),
+            }
+        }
+    }
+
+    match config.format {
+        Format::Text => println!("\nTotal Time: {}μs", total_time),
+        Format::Json => println!(
+            "{{\"results\":[{}],\"total_time_us\":{}}}",
+            outcomes
+                .iter()
+                .map(to_json)
+                .collect::<Vec<String>>()
+                .join(","),
+            total_time
+        ),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::run::{load_answers, to_json, Outcome};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_load_answers() {
+        let path = env::temp_dir().join(format!("aoc_rust_2021-answers-{}", std::process::id()));
+        fs::write(
+            &path,
+            "# day part answer\n1 1 1316\n1 2 1344  # sliding window\n",
+        )
+        .unwrap();
+        let answers = load_answers(path.to_str().unwrap()).unwrap();
+
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[&(1, 2)], "1344");
+
+        fs::write(&path, "1 1\n").unwrap();
+        assert!(load_answers(path.to_str().unwrap())
+            .unwrap_err()
+            .ends_with("line 1: expected day, part and answer"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_to_json() {
+        let mut outcome = Outcome {
+            day: 1,
+            part: 2,
+            solution: Ok(String::from("5")),
+            time: 12,
+            cached: false,
+            expected: Some(String::from("5")),
+            explanation: None,
+        };
+
+        assert_eq!(
+            to_json(&outcome),
+            "{\"day\":1,\"part\":2,\"answer\":\"5\",\"expected\":\"5\",\"correct\":true,\"time_us\":12,\"cached\":false}"
+        );
+
+        outcome.solution = Err(String::from("bad input"));
+        outcome.explanation = Some(vec![String::from("why")]);
+        assert_eq!(
+            to_json(&outcome),
+            "{\"day\":1,\"part\":2,\"error\":\"bad input\",\"time_us\":12,\"cached\":false,\"explanation\":[\"why\"]}"
+        );
+    }
+}
@@ -0,0 +1,435 @@
+use crate::cli::{json, option_value};
+use aoc_rust_2021::{days, find_day, AocError};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+const MAX_BODY_LENGTH: usize = 16 * 1024 * 1024;
+/// Length of the request line and headers together.
+const MAX_HEAD_LENGTH: u64 = 64 * 1024;
+/// Connections answered at the same time, each on its own thread.
+const MAX_CONNECTIONS: usize = 64;
+/// Time a client has to send each part of its request, or to read the response.
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Time a client has to send its whole request.
+const REQUEST_DEADLINE: Duration = Duration::from_secs(30);
+
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: format!("{{\"error\":{}}}", json::string(message)),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            self.body.len(),
+            self.body
+        )?;
+        writer.flush()
+    }
+}
+
+/// Reader failing with a timeout once `deadline` has passed, however slowly
+/// the client keeps sending its request.
+struct DeadlineReader<R> {
+    inner: R,
+    deadline: Instant,
+}
+
+impl<R: Read> Read for DeadlineReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if Instant::now() >= self.deadline {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.inner.read(buf)
+    }
+}
+
+fn read_error(error: io::Error) -> Response {
+    match error.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            Response::error(408, "timed out reading the HTTP request")
+        }
+        _ => Response::error(400, "malformed HTTP request"),
+    }
+}
+
+/// Next line of the request line and headers, all read from `head`.
+fn read_head_line(head: &mut io::Take<impl BufRead>) -> Result<String, Response> {
+    let mut line = String::new();
+    head.read_line(&mut line).map_err(read_error)?;
+
+    match !line.ends_with('\n') && head.limit() == 0 {
+        true => Err(Response::error(431, "HTTP request headers are too large")),
+        false => Ok(line),
+    }
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let mut head = reader.take(MAX_HEAD_LENGTH);
+    let request_line = read_head_line(&mut head)?;
+
+    let mut request_line_parts = request_line.split_whitespace();
+    let (method, path) = match (request_line_parts.next(), request_line_parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(Response::error(400, "malformed HTTP request")),
+    };
+
+    let mut content_length: usize = 0;
+    loop {
+        let header = read_head_line(&mut head)?;
+
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| Response::error(400, "invalid Content-Length header"))?;
+            }
+        }
+    }
+
+    if content_length > MAX_BODY_LENGTH {
+        return Err(Response::error(413, "puzzle input is too large"));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(read_error)?;
+
+    Ok(Request { method, path, body })
+}
+
+fn list_days() -> Response {
+    let days = days()
+        .iter()
+        .map(|day| {
+            format!(
+                "{{\"day\":{},\"title\":{}}}",
+                day.day,
+                json::string(day.title)
+            )
+        })
+        .collect::<Vec<String>>();
+
+    Response::ok(format!("{{\"days\":[{}]}}", days.join(",")))
+}
+
+fn solve_part(day: &str, part: &str, body: &[u8]) -> Response {
+    let (day, part) = match (day.parse::<u8>(), part.parse::<u8>()) {
+        (Ok(day), Ok(part)) => (day, part),
+        _ => return Response::error(404, "day and part must be numbers"),
+    };
+    let input = match std::str::from_utf8(body) {
+        Ok(input) => input,
+        Err(_) => return Response::error(400, "puzzle input is not valid UTF-8"),
+    };
+    let day = match find_day(day) {
+        Ok(day) => day,
+        Err(error) => return Response::error(404, &error.to_string()),
+    };
+
+    let chrono_start = Instant::now();
+    let solution_result = panic::catch_unwind(|| day.solve(part, input));
+    let chrono_stop = chrono_start.elapsed().as_micros();
+
+    match solution_result {
+        Ok(Ok(solution)) => Response::ok(format!(
+            "{{\"day\":{},\"part\":{},\"answer\":{},\"time_us\":{}}}",
+            day.day,
+            part,
+            json::string(&solution.to_string()),
+            chrono_stop
+        )),
+        Ok(Err(error @ AocError::UnknownPart(_))) => Response::error(404, &error.to_string()),
+        Ok(Err(error)) => Response::error(422, &error.to_string()),
+        Err(_) => Response::error(500, "the solver panicked on this input"),
+    }
+}
+
+fn route(request: &Request) -> Response {
+    let segments: Vec<&str> = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["days"]) => list_days(),
+        ("POST", ["days", day, "parts", part]) => solve_part(day, part, &request.body),
+        (_, ["days"]) | (_, ["days", _, "parts", _]) => {
+            Response::error(405, "method not allowed on this resource")
+        }
+        _ => Response::error(404, "no such resource"),
+    }
+}
+
+/// One of the [`MAX_CONNECTIONS`] connections being answered, given back when
+/// dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn acquire(connections: &Arc<AtomicUsize>) -> Option<ConnectionSlot> {
+        connections
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < MAX_CONNECTIONS).then_some(count + 1)
+            })
+            .ok()
+            .map(|_| ConnectionSlot(Arc::clone(connections)))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn handle_connection(stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(DeadlineReader {
+        inner: stream.try_clone()?,
+        deadline: Instant::now() + REQUEST_DEADLINE,
+    });
+    let response = match read_request(&mut reader) {
+        Ok(request) => route(&request),
+        Err(response) => response,
+    };
+
+    response.write_to(&mut &stream)
+}
+
+pub fn main(args: &[String]) -> Result<(), String> {
+    let mut address = DEFAULT_ADDRESS;
+    let mut index = 0;
+
+    while index < args.len() {
+        match args[index].as_str() {
+            "--address" => address = option_value(args, &mut index)?,
+            other => return Err(format!("unknown serve option: {}", other)),
+        }
+        index += 1;
+    }
+
+    let listener = TcpListener::bind(address).map_err(|error| format!("{}: {}", address, error))?;
+    let local_address = listener.local_addr().map_err(|error| error.to_string())?;
+
+    println!("Listening on http://{}", local_address);
+    io::stdout().flush().map_err(|error| error.to_string())?;
+
+    let connections = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => match ConnectionSlot::acquire(&connections) {
+                Some(slot) => {
+                    thread::spawn(move || {
+                        if let Err(error) = handle_connection(stream) {
+                            eprintln!("Could not answer request: {}", error);
+                        }
+                        drop(slot);
+                    });
+                }
+                None => {
+                    let busy = Response::error(503, "too many connections, try again later");
+                    stream
+                        .set_write_timeout(Some(TIMEOUT))
+                        .and_then(|()| busy.write_to(&mut &stream))
+                        .ok();
+                }
+            },
+            Err(error) => eprintln!("Could not accept connection: {}", error),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::serve::{
+        read_request, route, ConnectionSlot, DeadlineReader, Request, Response, MAX_CONNECTIONS,
+    };
+    use std::io::{self, BufReader, Read};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_read_request() {
+        let mut raw: &[u8] =
+            b"POST /days/6/parts/1 HTTP/1.1\r\nHost: localhost\r\ncontent-length: 10\r\n\r\n3,4,3,1,2\n";
+
+        assert_eq!(
+            read_request(&mut raw),
+            Ok(request("POST", "/days/6/parts/1", "3,4,3,1,2\n"))
+        );
+    }
+
+    #[test]
+    fn test_read_request_malformed() {
+        let mut raw: &[u8] = b"\r\n";
+
+        assert_eq!(read_request(&mut raw).unwrap_err().status, 400);
+    }
+
+    #[test]
+    fn test_read_request_timeout() {
+        struct SlowClient;
+
+        impl Read for SlowClient {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::WouldBlock.into())
+            }
+        }
+
+        let response = read_request(&mut BufReader::new(SlowClient)).unwrap_err();
+        assert_eq!(
+            (response.status, response.reason()),
+            (408, "Request Timeout")
+        );
+    }
+
+    #[test]
+    fn test_read_request_endless_header() {
+        let raw = b"GET /days HTTP/1.1\r\nX-Padding: ".chain(io::repeat(b'a'));
+
+        let response = read_request(&mut BufReader::new(raw)).unwrap_err();
+        assert_eq!(
+            (response.status, response.reason()),
+            (431, "Request Header Fields Too Large")
+        );
+    }
+
+    #[test]
+    fn test_read_request_deadline() {
+        // Sends a byte of an endless request line every millisecond, always
+        // within the timeout of each read.
+        struct TricklingClient;
+
+        impl Read for TricklingClient {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                thread::sleep(Duration::from_millis(1));
+                buf[0] = b'G';
+                Ok(1)
+            }
+        }
+
+        let mut reader = BufReader::new(DeadlineReader {
+            inner: TricklingClient,
+            deadline: Instant::now() + Duration::from_millis(50),
+        });
+        assert_eq!(read_request(&mut reader).unwrap_err().status, 408);
+    }
+
+    #[test]
+    fn test_connection_slots() {
+        let connections = Arc::new(AtomicUsize::new(0));
+        let mut slots: Vec<ConnectionSlot> = (0..MAX_CONNECTIONS)
+            .map(|_| ConnectionSlot::acquire(&connections).unwrap())
+            .collect();
+
+        assert!(ConnectionSlot::acquire(&connections).is_none());
+        slots.pop();
+        assert!(ConnectionSlot::acquire(&connections).is_some());
+        drop(slots);
+        assert_eq!(connections.load(Ordering::Acquire), 0);
+    }
+
+    #[cfg(feature = "day6")]
+    #[test]
+    fn test_route_solve() {
+        let response = route(&request("POST", "/days/6/parts/1", "3,4,3,1,2\n"));
+
+        assert_eq!(response.status, 200);
+        assert!(response
+            .body
+            .starts_with("{\"day\":6,\"part\":1,\"answer\":\"5934\",\"time_us\":"));
+    }
+
+    #[test]
+    fn test_route_errors() {
+        assert_eq!(
+            route(&request("POST", "/days/42/parts/1", "")),
+            Response::error(404, "no solver available for day 42")
+        );
+        #[cfg(feature = "day1")]
+        assert_eq!(route(&request("POST", "/days/1/parts/3", "")).status, 404);
+        #[cfg(feature = "day10")]
+        assert_eq!(
+            route(&request("POST", "/days/10/parts/2", "")),
+            Response::error(422, "Could not find middle score")
+        );
+        assert_eq!(route(&request("GET", "/days/1/parts/1", "")).status, 405);
+        assert_eq!(route(&request("GET", "/", "")).status, 404);
+    }
+
+    #[test]
+    fn test_route_list_days() {
+        let response = route(&request("GET", "/days", ""));
+
+        assert_eq!(response.status, 200);
+        assert!(response.body.starts_with("{\"days\":[") && response.body.ends_with("]}"));
+        #[cfg(feature = "day1")]
+        assert!(response
+            .body
+            .starts_with("{\"days\":[{\"day\":1,\"title\":\"Sonar Sweep\"},"));
+    }
+}
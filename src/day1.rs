@@ -82,52 +82,558 @@ In this example, there are 5 sums that are larger than the previous sum.
 Consider sums of a three-measurement sliding window. How many sums are larger than the previous sum?
 */
 
-pub fn part1(input: &str) -> i32 {
-    let mut measurements = input.split('\n').into_iter().peekable();
-    let mut count: i32 = 0;
-
-    while let Some(measurement) = measurements.next() {
-        if measurement.parse::<i32>().unwrap_or(0)
-            < measurements
-                .peek()
-                .unwrap_or(&"0")
-                .parse::<i32>()
-                .unwrap_or(0)
-        {
-            count += 1;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::rng::Rng;
+use crate::{AocError, Explain};
+
+/// How the sums of consecutive windows of measurements compare.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub increases: usize,
+    pub decreases: usize,
+    pub unchanged: usize,
+}
+
+/// Sums of `window` consecutive measurements compared as the measurements come,
+/// keeping only the last `window` of them. A window of 0 compares nothing.
+pub struct RollingWindow {
+    window: usize,
+    readings: VecDeque<i32>,
+    window_sum: i64,
+    changes: Changes,
+}
+
+impl RollingWindow {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            readings: VecDeque::new(),
+            window_sum: 0,
+            changes: Changes::default(),
+        }
+    }
+
+    pub fn push(&mut self, measurement: i32) {
+        if self.window == 0 {
+            return;
+        }
+
+        self.readings.push_back(measurement);
+        if self.readings.len() <= self.window {
+            self.window_sum += i64::from(measurement);
+            return;
+        }
+
+        let oldest_measurement = self.readings.pop_front().unwrap_or_default();
+        let next_window_sum =
+            self.window_sum + i64::from(measurement) - i64::from(oldest_measurement);
+
+        match next_window_sum.cmp(&self.window_sum) {
+            Ordering::Greater => self.changes.increases += 1,
+            Ordering::Less => self.changes.decreases += 1,
+            Ordering::Equal => self.changes.unchanged += 1,
+        }
+        self.window_sum = next_window_sum;
+    }
+
+    pub fn changes(&self) -> Changes {
+        self.changes
+    }
+}
+
+/// Consecutive measurements, from index `start` to index `end` included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Run {
+    pub fn length(&self) -> usize {
+        self.end - self.start + 1
+    }
+}
+
+/// Change of depth from the measurement before `index` to the one at `index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jump {
+    pub index: usize,
+    pub change: i64,
+}
+
+/// Measurement far from the average of the measurements just before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anomaly {
+    pub index: usize,
+    pub depth: i32,
+    pub moving_average: f64,
+}
+
+/// Statistics of the measurements of a sonar sweep, see [`SonarSweep::profile`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthProfile {
+    pub min_depth: i32,
+    pub max_depth: i32,
+    pub longest_increasing_run: Run,
+    pub longest_decreasing_run: Run,
+    pub largest_increase: Option<Jump>,
+    pub largest_decrease: Option<Jump>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl fmt::Display for DepthProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe_jump = |jump: Option<Jump>| match jump {
+            Some(jump) => format!("{:+} at index {}", jump.change, jump.index),
+            None => String::from("none"),
+        };
+
+        writeln!(f, "Depths from {} to {}", self.min_depth, self.max_depth)?;
+        for (direction, run) in [
+            ("increasing", self.longest_increasing_run),
+            ("decreasing", self.longest_decreasing_run),
+        ] {
+            writeln!(
+                f,
+                "Longest {} run: {} measurements, from index {} to {}",
+                direction,
+                run.length(),
+                run.start,
+                run.end
+            )?;
+        }
+        writeln!(
+            f,
+            "Largest increase: {}",
+            describe_jump(self.largest_increase)
+        )?;
+        writeln!(
+            f,
+            "Largest decrease: {}",
+            describe_jump(self.largest_decrease)
+        )?;
+        write!(f, "Anomalies: {}", self.anomalies.len())?;
+        for anomaly in &self.anomalies {
+            write!(
+                f,
+                "\n  index {}: {} (moving average {:.1})",
+                anomaly.index, anomaly.depth, anomaly.moving_average
+            )?;
         }
+
+        Ok(())
     }
+}
 
-    count
+/// Depth measurements of a sonar sweep.
+pub struct SonarSweep {
+    measurements: Vec<i32>,
 }
 
-pub fn part2(input: &str) -> u32 {
-    let measurements: Vec<i32> = input
+impl SonarSweep {
+    pub fn new(measurements: Vec<i32>) -> Self {
+        Self { measurements }
+    }
+
+    pub fn measurements(&self) -> &[i32] {
+        &self.measurements
+    }
+
+    /// Compare each sum of `window` consecutive measurements with the previous
+    /// one. The sums are rolled over the measurements, so any window size takes
+    /// a single pass. A window of 0 compares nothing.
+    pub fn changes(&self, window: usize) -> Changes {
+        let mut rolling_window = RollingWindow::new(window);
+
+        for &measurement in &self.measurements {
+            rolling_window.push(measurement);
+        }
+
+        rolling_window.changes()
+    }
+
+    pub fn increases(&self, window: usize) -> usize {
+        self.changes(window).increases
+    }
+
+    /// Measurements that differ by more than `threshold` from the average of the
+    /// `window` measurements before them. The first `window` measurements are
+    /// not checked.
+    pub fn anomalies(&self, window: usize, threshold: f64) -> Vec<Anomaly> {
+        let mut anomalies = Vec::new();
+        let mut window_sum: i64 = 0;
+
+        if window == 0 {
+            return anomalies;
+        }
+
+        for (index, &depth) in self.measurements.iter().enumerate() {
+            if index >= window {
+                let moving_average = window_sum as f64 / window as f64;
+
+                if (f64::from(depth) - moving_average).abs() > threshold {
+                    anomalies.push(Anomaly {
+                        index,
+                        depth,
+                        moving_average,
+                    });
+                }
+                window_sum -= i64::from(self.measurements[index - window]);
+            }
+            window_sum += i64::from(depth);
+        }
+
+        anomalies
+    }
+
+    /// Statistics of the measurements, with the anomalies found by
+    /// [`SonarSweep::anomalies`]. Ties go to the earliest run or jump.
+    pub fn profile(&self, window: usize, threshold: f64) -> Option<DepthProfile> {
+        let first_depth = *self.measurements.first()?;
+        let mut increasing_run = Run { start: 0, end: 0 };
+        let mut decreasing_run = Run { start: 0, end: 0 };
+        let mut profile = DepthProfile {
+            min_depth: first_depth,
+            max_depth: first_depth,
+            longest_increasing_run: increasing_run,
+            longest_decreasing_run: decreasing_run,
+            largest_increase: None,
+            largest_decrease: None,
+            anomalies: self.anomalies(window, threshold),
+        };
+
+        for index in 1..self.measurements.len() {
+            let depth = self.measurements[index];
+            let change = i64::from(depth) - i64::from(self.measurements[index - 1]);
+
+            profile.min_depth = profile.min_depth.min(depth);
+            profile.max_depth = profile.max_depth.max(depth);
+
+            increasing_run = match change > 0 {
+                true => Run {
+                    end: index,
+                    ..increasing_run
+                },
+                false => Run {
+                    start: index,
+                    end: index,
+                },
+            };
+            decreasing_run = match change < 0 {
+                true => Run {
+                    end: index,
+                    ..decreasing_run
+                },
+                false => Run {
+                    start: index,
+                    end: index,
+                },
+            };
+            if increasing_run.length() > profile.longest_increasing_run.length() {
+                profile.longest_increasing_run = increasing_run;
+            }
+            if decreasing_run.length() > profile.longest_decreasing_run.length() {
+                profile.longest_decreasing_run = decreasing_run;
+            }
+
+            if change > 0
+                && profile
+                    .largest_increase
+                    .is_none_or(|jump| change > jump.change)
+            {
+                profile.largest_increase = Some(Jump { index, change });
+            }
+            if change < 0
+                && profile
+                    .largest_decrease
+                    .is_none_or(|jump| change < jump.change)
+            {
+                profile.largest_decrease = Some(Jump { index, change });
+            }
+        }
+
+        Some(profile)
+    }
+}
+
+pub fn parse_input(input: &str) -> Vec<i32> {
+    input
         .split('\n')
         .filter_map(|measurement| measurement.parse::<i32>().ok())
-        .collect();
+        .collect()
+}
+
+/// Same as [`SonarSweep::changes`], with one measurement per line of `reader`.
+/// Measurements are read as they come, so recordings of any length fit in memory.
+pub fn stream_changes(mut reader: impl BufRead, window: usize) -> io::Result<Changes> {
+    let mut rolling_window = RollingWindow::new(window);
+    let mut line = String::new();
+
+    while reader.read_line(&mut line)? > 0 {
+        if let Ok(measurement) = line.trim_end_matches('\n').parse::<i32>() {
+            rolling_window.push(measurement);
+        }
+        line.clear();
+    }
+
+    Ok(rolling_window.changes())
+}
+
+/// Number of increases in `changes`, or an error if it does not fit in `T`.
+fn stream_increases<T: TryFrom<usize>>(changes: Changes) -> io::Result<T> {
+    T::try_from(changes.increases)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Too many depth increases"))
+}
+
+/// [`part1`] of a recording read from `reader`.
+pub fn stream_part1(reader: impl BufRead) -> io::Result<i32> {
+    stream_increases(stream_changes(reader, 1)?)
+}
 
-    let window_sums: Vec<i32> = measurements
-        .windows(3)
-        .map(|x| x[0] + x[1] + x[2])
-        .collect();
+/// [`part2`] of a recording read from `reader`.
+pub fn stream_part2(reader: impl BufRead) -> io::Result<u32> {
+    stream_increases(stream_changes(reader, 3)?)
+}
 
-    let increases = window_sums.windows(2).filter(|x| x[0] < x[1]).count();
+pub fn part1(input: &str) -> Result<i32, &'static str> {
+    i32::try_from(SonarSweep::new(parse_input(input)).increases(1))
+        .map_err(|_| "Too many depth increases")
+}
 
-    increases.try_into().unwrap()
+pub fn part2(input: &str) -> Result<u32, &'static str> {
+    u32::try_from(SonarSweep::new(parse_input(input)).increases(3))
+        .map_err(|_| "Too many depth increases")
+}
+
+pub struct Explanation;
+
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        let sonar_sweep = SonarSweep::new(parse_input(input));
+        let measurements = sonar_sweep.measurements().len();
+
+        Ok(match part {
+            1 => {
+                let changes = sonar_sweep.changes(1);
+
+                vec![format!(
+                    "{} of the {} measurements are larger than the previous one ({} smaller, {} unchanged)",
+                    changes.increases, measurements, changes.decreases, changes.unchanged
+                )]
+            }
+            _ => {
+                let changes = sonar_sweep.changes(3);
+
+                vec![format!(
+                    "{} of the {} sums of three-measurement windows are larger than the previous one ({} smaller, {} unchanged)",
+                    changes.increases,
+                    measurements.saturating_sub(2),
+                    changes.decreases,
+                    changes.unchanged
+                )]
+            }
+        })
+    }
+}
+
+/// Random sonar report of `size` depth measurements, mostly increasing.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut depth = rng.range(100, 200) as i64;
+    let mut report = String::new();
+
+    for _ in 0..size {
+        report.push_str(&format!("{}\n", depth));
+        depth = (depth + rng.range(0, 30) as i64 - 10).max(0);
+    }
+
+    report
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day1::{part1, part2};
+    use crate::day1::{
+        generate, parse_input, part1, part2, stream_changes, stream_increases, stream_part1,
+        stream_part2, Anomaly, Changes, Explanation, Jump, Run, SonarSweep,
+    };
+    use crate::rng::Rng;
+    use crate::Explain;
+    use std::io;
+
+    static TEST_INPUT: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1("199\n200\n208\n210\n200\n207\n240\n269\n260\n263"), 7);
+        assert_eq!(
+            part1("199\n200\n208\n210\n200\n207\n240\n269\n260\n263"),
+            Ok(7)
+        );
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2("199\n200\n208\n210\n200\n207\n240\n269\n260\n263"), 5);
+        assert_eq!(
+            part2("199\n200\n208\n210\n200\n207\n240\n269\n260\n263"),
+            Ok(5)
+        );
+    }
+
+    #[test]
+    fn test_sonar_sweep_changes() {
+        let sonar_sweep = SonarSweep::new(parse_input(TEST_INPUT));
+
+        assert_eq!(
+            sonar_sweep.changes(1),
+            Changes {
+                increases: 7,
+                decreases: 2,
+                unchanged: 0
+            }
+        );
+        assert_eq!(
+            sonar_sweep.changes(3),
+            Changes {
+                increases: 5,
+                decreases: 1,
+                unchanged: 1
+            }
+        );
+        assert_eq!(sonar_sweep.increases(10), 0);
+        assert_eq!(sonar_sweep.changes(0), Changes::default());
+    }
+
+    #[test]
+    fn test_sonar_sweep_any_window() {
+        let sonar_sweep = SonarSweep::new(vec![1, 3, 2, 4, 3, 5, 4, 6]);
+
+        // Sums of two: 4, 5, 6, 7, 8, 9, 10.
+        assert_eq!(sonar_sweep.increases(2), 6);
+        // Sums of four: 10, 12, 14, 16, 18.
+        assert_eq!(sonar_sweep.changes(4).decreases, 0);
+        assert_eq!(
+            sonar_sweep.changes(7),
+            Changes {
+                increases: 1,
+                decreases: 0,
+                unchanged: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_profile() {
+        let profile = SonarSweep::new(parse_input(TEST_INPUT))
+            .profile(3, 20.0)
+            .unwrap();
+
+        assert_eq!(profile.min_depth, 199);
+        assert_eq!(profile.max_depth, 269);
+        assert_eq!(profile.longest_increasing_run, Run { start: 0, end: 3 });
+        assert_eq!(profile.longest_decreasing_run, Run { start: 3, end: 4 });
+        assert_eq!(
+            profile.largest_increase,
+            Some(Jump {
+                index: 6,
+                change: 33
+            })
+        );
+        assert_eq!(
+            profile.largest_decrease,
+            Some(Jump {
+                index: 4,
+                change: -10
+            })
+        );
+        assert_eq!(
+            profile
+                .anomalies
+                .iter()
+                .map(|anomaly| anomaly.index)
+                .collect::<Vec<usize>>(),
+            vec![6, 7, 8]
+        );
+        assert!(profile
+            .to_string()
+            .starts_with("Depths from 199 to 269\nLongest increasing run: 4 measurements"));
+
+        assert_eq!(SonarSweep::new(vec![]).profile(3, 20.0), None);
+    }
+
+    #[test]
+    fn test_profile_single_measurement() {
+        let profile = SonarSweep::new(vec![42]).profile(1, 0.0).unwrap();
+
+        assert_eq!(profile.longest_increasing_run.length(), 1);
+        assert_eq!(profile.largest_increase, None);
+        assert!(profile.anomalies.is_empty());
+    }
+
+    #[test]
+    fn test_anomalies() {
+        let sonar_sweep = SonarSweep::new(vec![100, 102, 98, 100, 150, 101]);
+
+        assert_eq!(
+            sonar_sweep.anomalies(2, 10.0),
+            vec![
+                Anomaly {
+                    index: 4,
+                    depth: 150,
+                    moving_average: 99.0
+                },
+                Anomaly {
+                    index: 5,
+                    depth: 101,
+                    moving_average: 125.0
+                }
+            ]
+        );
+        assert!(sonar_sweep.anomalies(0, 10.0).is_empty());
+        assert_eq!(sonar_sweep.anomalies(4, 100.0), vec![]);
+    }
+
+    #[test]
+    fn test_stream() {
+        assert_eq!(stream_part1(TEST_INPUT.as_bytes()).unwrap(), 7);
+        assert_eq!(stream_part2(TEST_INPUT.as_bytes()).unwrap(), 5);
+        assert_eq!(stream_part2("".as_bytes()).unwrap(), 0);
+
+        let changes = Changes {
+            increases: i32::MAX as usize + 1,
+            ..Changes::default()
+        };
+        assert_eq!(stream_increases::<u32>(changes).unwrap(), 1 << 31);
+        assert_eq!(
+            stream_increases::<i32>(changes).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_stream_same_as_sonar_sweep() {
+        let mut rng = Rng::new(1);
+
+        for _ in 0..20 {
+            let size = rng.range(1, 200) as usize;
+            let input = generate(&mut rng, size);
+            let sonar_sweep = SonarSweep::new(parse_input(&input));
+
+            for window in 0..6 {
+                assert_eq!(
+                    stream_changes(input.as_bytes(), window).unwrap(),
+                    sonar_sweep.changes(window)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_explanation() {
+        assert_eq!(
+            Explanation::explain(1, TEST_INPUT).unwrap(),
+            vec!["7 of the 10 measurements are larger than the previous one (2 smaller, 0 unchanged)"]
+        );
     }
 }
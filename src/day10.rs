@@ -100,6 +100,11 @@ Autocomplete tools are an odd bunch: the winner is found by sorting all of the s
 Find the completion string for each incomplete line, score the completion strings, and sort the scores. What is the middle score?
 */
 
+use crate::rng::Rng;
+use crate::trace::Level;
+use crate::trace_event;
+use crate::{AocError, Explain};
+
 #[derive(Debug, PartialEq)]
 enum LineStatus {
     Corrupted { points: u64 },
@@ -136,7 +141,12 @@ fn parse_line(line: &str) -> Option<LineStatus> {
                 None => {}
             },
             invalid_char => {
-                println!("Found incompatible char: {}", invalid_char)
+                trace_event!(
+                    Level::Warn,
+                    "day10",
+                    "Found incompatible char: {}",
+                    invalid_char
+                )
             }
         }
     }
@@ -201,9 +211,128 @@ pub fn part2(input: &str) -> Result<u64, &'static str> {
     }
 }
 
+pub struct Explanation;
+
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        let mut corrupted: Vec<u64> = Vec::new();
+        let mut incomplete: Vec<Vec<char>> = Vec::new();
+
+        for line in input.split('\n') {
+            match parse_line(line) {
+                Some(LineStatus::Corrupted { points }) => corrupted.push(points),
+                Some(LineStatus::Incomplete { non_matched }) => incomplete.push(non_matched),
+                None => {}
+            }
+        }
+
+        let mut lines = vec![format!(
+            "{} corrupted lines and {} incomplete lines",
+            corrupted.len(),
+            incomplete.len()
+        )];
+
+        match part {
+            1 => {
+                for (character, points) in [(')', 3), (']', 57), ('}', 1197), ('>', 25137)] {
+                    let count = corrupted.iter().filter(|&&line| line == points).count();
+
+                    if count > 0 {
+                        lines.push(format!(
+                            "{} lines corrupted by '{}', worth {} points each",
+                            count, character, points
+                        ));
+                    }
+                }
+                lines.push(format!(
+                    "Total syntax error score: {}",
+                    corrupted.iter().sum::<u64>()
+                ));
+            }
+            _ => {
+                let mut scores: Vec<u64> = incomplete
+                    .into_iter()
+                    .map(|mut non_matched| {
+                        non_matched.reverse();
+                        autocomplete_score(non_matched)
+                    })
+                    .collect();
+                scores.sort();
+
+                let middle_score = scores
+                    .get(scores.len() / 2)
+                    .ok_or("Could not find middle score")?;
+
+                lines.push(format!(
+                    "Sorted completion scores: {}",
+                    scores
+                        .iter()
+                        .map(u64::to_string)
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ));
+                lines.push(format!("Middle score: {}", middle_score));
+            }
+        }
+
+        Ok(lines)
+    }
+}
+
+/// Random navigation subsystem of `size` lines, corrupted or incomplete, with
+/// an odd number of incomplete lines so there is a middle score.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const CHUNKS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+    let size = size.max(1);
+    // Between 1 and size incomplete lines, rounded down to an odd number.
+    let incomplete_lines = (rng.range(1, size as u64) as usize - 1) | 1;
+    let mut incomplete: Vec<bool> = (0..size).map(|line| line < incomplete_lines).collect();
+    rng.shuffle(&mut incomplete);
+
+    let mut lines = String::new();
+
+    for is_incomplete in incomplete {
+        let mut stack: Vec<char> = Vec::new();
+
+        for _ in 0..rng.range(10, 100) {
+            // At most 20 chunks open, for the autocomplete score to fit in 64 bits.
+            if !stack.is_empty() && (stack.len() == 20 || rng.chance(40)) {
+                lines.extend(stack.pop());
+            } else {
+                let (opening, closing) = *rng.choose(&CHUNKS);
+                lines.push(opening);
+                stack.push(closing);
+            }
+        }
+
+        if stack.is_empty() {
+            let (opening, closing) = *rng.choose(&CHUNKS);
+            lines.push(opening);
+            stack.push(closing);
+        }
+
+        if !is_incomplete {
+            let expected = stack.last().copied();
+            let wrong = loop {
+                let (_, closing) = *rng.choose(&CHUNKS);
+                if Some(closing) != expected {
+                    break closing;
+                }
+            };
+            lines.push(wrong);
+        }
+
+        lines.push('\n');
+    }
+
+    lines
+}
+
 #[cfg(test)]
 mod tests {
     use crate::day10::{autocomplete_score, parse_line, part1, part2, LineStatus};
+    use crate::rng::Rng;
 
     static TEST_INPUT: &str = "[({(<(())[]>[[{[]{<()<>>
 [(()[<>])]({[<{<<[]>>(
@@ -276,4 +405,42 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok(288957));
     }
+
+    /// Random string of `chunks` chunks, every one of them closed.
+    fn balanced(rng: &mut Rng, chunks: usize) -> String {
+        const CHUNKS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+        if chunks == 0 {
+            return String::new();
+        }
+
+        let inner = rng.below(chunks as u64) as usize;
+        let (opening, closing) = *rng.choose(&CHUNKS);
+
+        format!(
+            "{}{}{}{}",
+            opening,
+            balanced(rng, inner),
+            closing,
+            balanced(rng, chunks - 1 - inner)
+        )
+    }
+
+    #[test]
+    fn test_balanced_lines_are_never_corrupted() {
+        let mut rng = Rng::new(10);
+
+        for _ in 0..100 {
+            let chunks = rng.range(1, 30) as usize;
+            let line = balanced(&mut rng, chunks);
+
+            assert_eq!(parse_line(&line), None);
+            for end in 1..line.len() {
+                assert!(!matches!(
+                    parse_line(&line[..end]),
+                    Some(LineStatus::Corrupted { .. })
+                ));
+            }
+        }
+    }
 }
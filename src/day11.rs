@@ -300,7 +300,12 @@ After 100 steps, there have been a total of 1656 flashes.
 Given the starting energy levels of the dumbo octopuses in your cavern, simulate 100 steps. How many total flashes are there after 100 steps?
 */
 
-fn parse_input(input: &str) -> Vec<Vec<u32>> {
+use crate::rng::Rng;
+use crate::trace::Level;
+use crate::trace_event;
+use crate::{AocError, Explain};
+
+pub fn parse_input(input: &str) -> Result<Vec<Vec<u32>>, &'static str> {
     let mut octopus_grid: Vec<Vec<u32>> = Vec::new();
 
     for line in input.split('\n') {
@@ -313,7 +318,14 @@ fn parse_input(input: &str) -> Vec<Vec<u32>> {
         }
     }
 
-    octopus_grid
+    if octopus_grid
+        .iter()
+        .any(|row| row.len() != octopus_grid[0].len())
+    {
+        return Err("Rows of octopuses must have the same length");
+    }
+
+    Ok(octopus_grid)
 }
 
 fn increase_energy_level(grid: &mut [Vec<u32>]) {
@@ -322,7 +334,7 @@ fn increase_energy_level(grid: &mut [Vec<u32>]) {
     });
 }
 
-fn flash(grid: &mut Vec<Vec<u32>>, y: usize, x: usize) {
+fn flash(grid: &mut [Vec<u32>], y: usize, x: usize) {
     if (y < grid.len()) && (x < grid[y].len()) {
         // Row above.
         if y > 0 {
@@ -400,30 +412,162 @@ fn step(grid: &mut Vec<Vec<u32>>, number_of_steps: u64) -> u64 {
     number_of_flashes
 }
 
-pub fn part1(input: &str) -> Result<u64, &'static str> {
-    let mut grid: Vec<Vec<u32>> = parse_input(input);
+/// Flashes during each of the first `steps` steps of `grid`.
+fn flashes_per_step(grid: &mut Vec<Vec<u32>>, steps: u64) -> Vec<u64> {
+    (1..=steps)
+        .map(|step_number| {
+            let flashes = step(grid, 1);
+            trace_event!(
+                Level::Trace,
+                "day11",
+                "Step {}: {} flashes",
+                step_number,
+                flashes
+            );
+            flashes
+        })
+        .collect()
+}
+
+/// Flashes during each step of `grid`, up to the first one where every
+/// octopus flashes.
+fn flashes_until_synchronised(grid: &mut Vec<Vec<u32>>) -> Result<Vec<u64>, &'static str> {
+    let octopuses = grid.iter().map(Vec::len).sum::<usize>() as u64;
+    let mut flashes_per_step = Vec::new();
+    // Grid seen at the last power of two steps, to detect cycles (Brent's algorithm).
+    let mut saved_grid = grid.clone();
+    let mut cycle_length: u64 = 1;
+
+    if octopuses == 0 {
+        return Err("No octopus in the input");
+    }
 
-    Ok(step(&mut grid, 100))
+    loop {
+        let flashes = step(grid, 1);
+        flashes_per_step.push(flashes);
+        let step_number = flashes_per_step.len() as u64;
+        trace_event!(
+            Level::Trace,
+            "day11",
+            "Step {}: {} flashes",
+            step_number,
+            flashes
+        );
+
+        if flashes == octopuses {
+            trace_event!(
+                Level::Debug,
+                "day11",
+                "All octopuses flashed during step {}",
+                step_number
+            );
+            return Ok(flashes_per_step);
+        }
+
+        // The grid went back to an earlier state without synchronising: it never will.
+        if *grid == saved_grid {
+            return Err("The octopuses never flash simultaneously");
+        }
+        if step_number == cycle_length {
+            saved_grid = grid.clone();
+            cycle_length *= 2;
+        }
+    }
+}
+
+pub fn part1(input: &str) -> Result<u64, &'static str> {
+    Ok(flashes_per_step(&mut parse_input(input)?, 100).iter().sum())
 }
 
 pub fn part2(input: &str) -> Result<u64, &'static str> {
-    let mut grid: Vec<Vec<u32>> = parse_input(input);
-    let mut step_number: u64 = 1;
+    Ok(flashes_until_synchronised(&mut parse_input(input)?)?.len() as u64)
+}
 
-    loop {
-        if step(&mut grid, 1) == 100 {
-            return Ok(step_number);
+pub struct Explanation;
+
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        let mut grid: Vec<Vec<u32>> = parse_input(input)?;
+        let octopuses = grid.iter().map(Vec::len).sum::<usize>() as u64;
+
+        match part {
+            1 => {
+                let flashes_per_step = flashes_per_step(&mut grid, 100);
+                let (busiest_step, most_flashes) = flashes_per_step
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .max_by_key(|&(_, flashes)| flashes)
+                    .unwrap_or((0, &0));
+
+                Ok(vec![
+                    format!(
+                        "{} flashes during the first 100 steps",
+                        flashes_per_step.iter().sum::<u64>()
+                    ),
+                    format!(
+                        "Most flashes in one step: {}, during step {}",
+                        most_flashes,
+                        busiest_step + 1
+                    ),
+                ])
+            }
+            _ => {
+                let flashes_per_step = flashes_until_synchronised(&mut grid)?;
+
+                Ok(vec![
+                    format!(
+                        "All {} octopuses flash together for the first time during step {}",
+                        octopuses,
+                        flashes_per_step.len()
+                    ),
+                    format!(
+                        "{} flashes happened before that step",
+                        flashes_per_step.iter().sum::<u64>() - octopuses
+                    ),
+                ])
+            }
         }
+    }
+}
 
-        step_number += 1;
+/// Random grid of `size` by `size` octopuses that synchronise, as the puzzle
+/// expects. Energy levels are kept within a window of seven: larger grids of
+/// fully random levels rarely ever synchronise.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    loop {
+        let lowest_energy_level = rng.below(4) as u32;
+        let grid: Vec<Vec<u32>> = (0..size)
+            .map(|_| {
+                (0..size)
+                    .map(|_| lowest_energy_level + rng.below(7) as u32)
+                    .collect()
+            })
+            .collect();
+
+        let mut simulation = grid.clone();
+        if (0..1000).any(|_| step(&mut simulation, 1) == (size * size) as u64) {
+            return grid
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .filter_map(|&energy_level| char::from_digit(energy_level, 10))
+                        .chain(['\n'])
+                        .collect::<String>()
+                })
+                .collect();
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day11::{
-        flash, increase_energy_level, is_octopus_ready_to_flash, parse_input, part1, part2, step,
+        flash, generate, increase_energy_level, is_octopus_ready_to_flash, parse_input, part1,
+        part2, step, Explanation,
     };
+    use crate::rng::Rng;
+    use crate::Explain;
 
     static SMALLER_TEST_INPUT: &str = "11111
 19991
@@ -447,7 +591,7 @@ mod tests {
     #[test]
     fn test_parse_input() {
         assert_eq!(
-            *parse_input(TEST_INPUT),
+            *parse_input(TEST_INPUT).unwrap(),
             vec![
                 vec![5, 4, 8, 3, 1, 4, 3, 2, 2, 3],
                 vec![2, 7, 4, 5, 8, 5, 4, 7, 1, 1],
@@ -465,7 +609,7 @@ mod tests {
 
     #[test]
     fn test_increase_energy_level() {
-        let grid = &mut parse_input(SMALLER_TEST_INPUT);
+        let grid = &mut parse_input(SMALLER_TEST_INPUT).unwrap();
 
         increase_energy_level(grid);
         assert_eq!(
@@ -482,7 +626,7 @@ mod tests {
 
     #[test]
     fn test_flash() {
-        let grid = &mut parse_input(SMALLER_TEST_INPUT);
+        let grid = &mut parse_input(SMALLER_TEST_INPUT).unwrap();
 
         flash(grid, 1, 1);
         assert_eq!(
@@ -583,7 +727,7 @@ mod tests {
 
     #[test]
     fn test_flash_with_point_out_of_grid() {
-        let grid = &mut parse_input(SMALLER_TEST_INPUT);
+        let grid = &mut parse_input(SMALLER_TEST_INPUT).unwrap();
 
         flash(grid, 5, 5);
         assert_eq!(
@@ -622,7 +766,7 @@ mod tests {
 
     #[test]
     fn test_step_1_smaller() {
-        let mut grid = parse_input(SMALLER_TEST_INPUT);
+        let mut grid = parse_input(SMALLER_TEST_INPUT).unwrap();
 
         assert_eq!(step(&mut grid, 1), 9);
         assert_eq!(
@@ -639,7 +783,7 @@ mod tests {
 
     #[test]
     fn test_step_2_smaller() {
-        let mut grid = parse_input(SMALLER_TEST_INPUT);
+        let mut grid = parse_input(SMALLER_TEST_INPUT).unwrap();
 
         assert_eq!(step(&mut grid, 2), 9);
         assert_eq!(
@@ -656,18 +800,91 @@ mod tests {
 
     #[test]
     fn test_step_100() {
-        let mut grid = parse_input(TEST_INPUT);
+        let mut grid = parse_input(TEST_INPUT).unwrap();
 
         assert_eq!(step(&mut grid, 100), 1656);
     }
 
+    #[test]
+    fn test_step_flashes_add_up() {
+        let mut rng = Rng::new(11);
+
+        for _ in 0..20 {
+            let size = rng.range(1, 10) as usize;
+            let number_of_steps = rng.below(50);
+            let mut grid: Vec<Vec<u32>> = (0..size)
+                .map(|_| (0..size).map(|_| rng.below(10) as u32).collect())
+                .collect();
+            let mut grid_step_by_step = grid.clone();
+
+            let total_flashes = step(&mut grid, number_of_steps);
+            let sum_of_flashes: u64 = (0..number_of_steps)
+                .map(|_| step(&mut grid_step_by_step, 1))
+                .sum();
+
+            assert_eq!(total_flashes, sum_of_flashes);
+            assert_eq!(grid, grid_step_by_step);
+        }
+
+        let input = generate(&mut rng, 8);
+        assert_eq!(
+            part1(&input),
+            Ok(step(&mut parse_input(&input).unwrap(), 100))
+        );
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(TEST_INPUT), Ok(1656));
     }
 
+    #[test]
+    fn test_parse_input_uneven_rows() {
+        assert_eq!(
+            parse_input("123\n45\n"),
+            Err("Rows of octopuses must have the same length")
+        );
+    }
+
     #[test]
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok(195));
+        assert_eq!(
+            part2("945\n807\n302\n"),
+            Err("The octopuses never flash simultaneously")
+        );
+    }
+
+    #[test]
+    fn test_part2_any_grid_size() {
+        let mut rng = Rng::new(11);
+
+        for size in 1..=6 {
+            let input = generate(&mut rng, size);
+            let mut grid = parse_input(&input).unwrap();
+            let steps = (1..)
+                .find(|_| step(&mut grid, 1) == (size * size) as u64)
+                .unwrap();
+
+            assert_eq!(part2(&input), Ok(steps));
+        }
+    }
+
+    #[test]
+    fn test_explanation() {
+        assert_eq!(
+            Explanation::explain(1, TEST_INPUT).unwrap()[0],
+            "1656 flashes during the first 100 steps"
+        );
+        assert_eq!(
+            Explanation::explain(2, TEST_INPUT).unwrap()[0],
+            "All 100 octopuses flash together for the first time during step 195"
+        );
+        assert!(Explanation::explain(2, "").is_err());
+
+        assert!(Explanation::explain(1, TEST_INPUT).unwrap()[0]
+            .starts_with(&format!("{} flashes", part1(TEST_INPUT).unwrap())));
+        assert!(Explanation::explain(2, TEST_INPUT).unwrap()[0]
+            .ends_with(&format!("during step {}", part2(TEST_INPUT).unwrap())));
     }
 }
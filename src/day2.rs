@@ -61,15 +61,390 @@ After following these new instructions, you would have a horizontal position of
 Using this new interpretation of the commands, calculate the horizontal position and depth you would have after following the planned course. What do you get if you multiply your final horizontal position by your final depth?
 */
 
+use crate::rng::Rng;
+use crate::{AocError, Explain};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Forward(i64),
+    Up(i64),
+    Down(i64),
+}
+
+/// Invalid command, `line` and `column` start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandError {
+    pub line: usize,
+    pub column: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// Error of a course: an invalid command, a submarine going out of the range
+/// of 64-bit integers, or a drag that is not a percentage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CourseError {
+    Command(CommandError),
+    Overflow,
+    Drag(i64),
+}
+
+impl fmt::Display for CourseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CourseError::Command(error) => write!(f, "{}", error),
+            CourseError::Overflow => write!(f, "the submarine position, depth or aim overflows"),
+            CourseError::Drag(drag) => {
+                write!(f, "the drag of {} percent is not between 0 and 100", drag)
+            }
+        }
+    }
+}
+
+impl From<CommandError> for CourseError {
+    fn from(error: CommandError) -> Self {
+        CourseError::Command(error)
+    }
+}
+
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let error = |column, message| CommandError {
+            line: 1,
+            column,
+            message,
+        };
+        let (name, value) = line.split_once(' ').unwrap_or((line, ""));
+        let command = match name {
+            "forward" => Command::Forward,
+            "up" => Command::Up,
+            "down" => Command::Down,
+            _ => return Err(error(1, "unsupported submarine command")),
+        };
+
+        if value.is_empty() {
+            return Err(error(name.len() + 1, "missing submarine command value"));
+        }
+
+        value
+            .parse()
+            .map(command)
+            .map_err(|_| error(name.len() + 2, "unsupported submarine command value"))
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Forward(value) => write!(f, "forward {}", value),
+            Command::Up(value) => write!(f, "up {}", value),
+            Command::Down(value) => write!(f, "down {}", value),
+        }
+    }
+}
+
+/// Maximum number of statements run, and of nested blocks, when expanding a
+/// course script.
+const MAX_EXPANSION: usize = 1_000_000;
+const MAX_NESTING: usize = 100;
+
+const KEYWORDS: [&str; 6] = ["forward", "up", "down", "repeat", "macro", "let"];
+
+/// Number, or `$name` of a variable (with its column).
+enum Value<'a> {
+    Number(i64),
+    Variable(&'a str, usize),
+}
+
+enum Statement<'a> {
+    Command(Command),
+    VariableCommand(fn(i64) -> Command, Value<'a>),
+    Let(&'a str, Value<'a>),
+    Repeat(Value<'a>, Vec<ScriptLine<'a>>),
+    Macro(&'a str, Vec<ScriptLine<'a>>),
+    Call(&'a str),
+}
+
+struct ScriptLine<'a> {
+    line: usize,
+    column: usize,
+    statement: Statement<'a>,
+}
+
+fn is_identifier(text: &str) -> bool {
+    text.starts_with(|character: char| character.is_ascii_alphabetic() || character == '_')
+        && text
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
+
+/// Column of `token`, a slice of `line`.
+fn column(line: &str, token: &str) -> usize {
+    token.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// Statements up to the `}` closing the block opened at `opening` (line and
+/// column), or up to the end of the script.
+fn parse_block<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    opening: Option<(usize, usize)>,
+    nesting: usize,
+) -> Result<Vec<ScriptLine<'a>>, CommandError> {
+    let mut block = Vec::new();
+
+    while let Some((line_number, line)) = lines.next() {
+        let code = line.split('#').next().unwrap_or("");
+        let error = |token, message| CommandError {
+            line: line_number,
+            column: column(line, token),
+            message,
+        };
+        let words: Vec<&str> = code.split_whitespace().collect();
+        let value = |token: &'a str| match token.strip_prefix('$') {
+            Some(name) if is_identifier(name) => Ok(Value::Variable(name, column(line, token))),
+            Some(_) => Err(error(token, "invalid variable name")),
+            None => token
+                .parse()
+                .map(Value::Number)
+                .map_err(|_| error(token, "unsupported submarine command value")),
+        };
+        let mut block_after = |brace| {
+            if nesting >= MAX_NESTING {
+                return Err(error(brace, "blocks nested too deeply"));
+            }
+            parse_block(lines, Some((line_number, column(line, brace))), nesting + 1)
+        };
+
+        let statement = match words.as_slice() {
+            [] => continue,
+            ["}"] if opening.is_some() => return Ok(block),
+            ["}"] => return Err(error(words[0], "unexpected '}'")),
+            ["repeat", count, brace @ "{"] => Statement::Repeat(value(count)?, block_after(brace)?),
+            ["repeat", ..] => return Err(error(words[0], "expected 'repeat COUNT {'")),
+            ["macro", name, brace @ "{"] if is_identifier(name) && !KEYWORDS.contains(name) => {
+                Statement::Macro(name, block_after(brace)?)
+            }
+            ["macro", ..] => return Err(error(words[0], "expected 'macro NAME {'")),
+            ["let", name, "=", number] if is_identifier(name) => {
+                Statement::Let(name, value(number)?)
+            }
+            ["let", ..] => return Err(error(words[0], "expected 'let NAME = VALUE'")),
+            [name, variable] if variable.starts_with('$') => {
+                let command = match *name {
+                    "forward" => Command::Forward,
+                    "up" => Command::Up,
+                    "down" => Command::Down,
+                    _ => return Err(error(name, "unsupported submarine command")),
+                };
+                Statement::VariableCommand(command, value(variable)?)
+            }
+            [name] if is_identifier(name) && !KEYWORDS.contains(name) => Statement::Call(name),
+            _ => {
+                let command = code.trim();
+                Statement::Command(
+                    command
+                        .parse()
+                        .map_err(|error: CommandError| CommandError {
+                            line: line_number,
+                            column: column(line, command) + error.column - 1,
+                            ..error
+                        })?,
+                )
+            }
+        };
+
+        block.push(ScriptLine {
+            line: line_number,
+            column: column(line, words[0]),
+            statement,
+        });
+    }
+
+    match opening {
+        Some((line, column)) => Err(CommandError {
+            line,
+            column,
+            message: "missing '}'",
+        }),
+        None => Ok(block),
+    }
+}
+
+/// State of the expansion of a course script into commands.
+#[derive(Default)]
+struct Expansion<'a> {
+    variables: HashMap<&'a str, i64>,
+    macros: HashMap<&'a str, &'a [ScriptLine<'a>]>,
+    calls: Vec<&'a str>,
+    nesting: usize,
+    statements: usize,
+    course: Vec<Command>,
+}
+
+impl<'a> Expansion<'a> {
+    fn value(&self, value: &Value, line: usize) -> Result<i64, CommandError> {
+        match *value {
+            Value::Number(number) => Ok(number),
+            Value::Variable(name, column) => {
+                self.variables.get(name).copied().ok_or(CommandError {
+                    line,
+                    column,
+                    message: "undefined variable",
+                })
+            }
+        }
+    }
+
+    fn run(&mut self, block: &'a [ScriptLine<'a>]) -> Result<(), CommandError> {
+        for script_line in block {
+            let error = |message| CommandError {
+                line: script_line.line,
+                column: script_line.column,
+                message,
+            };
+            let count_statement = |expansion: &mut Self| {
+                expansion.statements += 1;
+                match expansion.statements > MAX_EXPANSION {
+                    true => Err(error("the script expands to too many commands")),
+                    false => Ok(()),
+                }
+            };
+
+            count_statement(self)?;
+            match &script_line.statement {
+                Statement::Command(command) => self.course.push(*command),
+                Statement::VariableCommand(command, value) => {
+                    let value = self.value(value, script_line.line)?;
+                    self.course.push(command(value));
+                }
+                Statement::Let(name, value) => {
+                    let value = self.value(value, script_line.line)?;
+                    self.variables.insert(name, value);
+                }
+                Statement::Repeat(count, body) => {
+                    let count = self.value(count, script_line.line)?;
+
+                    if count < 0 {
+                        return Err(error("the repeat count must not be negative"));
+                    }
+                    for _ in 0..count {
+                        count_statement(self)?;
+                        self.run_nested(body, script_line)?;
+                    }
+                }
+                Statement::Macro(name, body) => {
+                    self.macros.insert(name, body);
+                }
+                Statement::Call(name) => {
+                    let body = *self.macros.get(name).ok_or(error("undefined macro"))?;
+
+                    if self.calls.contains(name) {
+                        return Err(error("recursive macro"));
+                    }
+                    self.calls.push(name);
+                    self.run_nested(body, script_line)?;
+                    self.calls.pop();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run the body of a repeat block or a macro called by `script_line`.
+    fn run_nested(
+        &mut self,
+        body: &'a [ScriptLine<'a>],
+        script_line: &ScriptLine,
+    ) -> Result<(), CommandError> {
+        if self.nesting >= MAX_NESTING {
+            return Err(CommandError {
+                line: script_line.line,
+                column: script_line.column,
+                message: "blocks and macro calls nested too deeply",
+            });
+        }
+
+        self.nesting += 1;
+        self.run(body)?;
+        self.nesting -= 1;
+
+        Ok(())
+    }
+}
+
+/// Commands of a planned course, one per line. Blank lines are skipped.
+///
+/// A course can also be a script with `# comments`, `repeat COUNT { ... }`
+/// blocks, macros defined by `macro NAME { ... }` and called by `NAME`, and
+/// variables set by `let NAME = VALUE` and read as `$NAME`:
+///
+/// ```text
+/// let step = 5
+/// macro dive {
+///     down $step
+///     forward 2  # level off
+/// }
+/// repeat 3 {
+///     dive
+/// }
+/// ```
+pub fn parse_course(input: &str) -> Result<Vec<Command>, CommandError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line));
+    let script = parse_block(&mut lines, None, 0)?;
+    let mut expansion = Expansion::default();
+
+    expansion.run(&script)?;
+
+    Ok(expansion.course)
+}
+
+/// Steering model of a submarine following a course.
+pub trait Navigator {
+    fn apply(&mut self, command: Command) -> Result<(), CourseError>;
+    fn horizontal_position(&self) -> i64;
+    fn depth(&self) -> i64;
+
+    /// Aim of the models steering with one.
+    fn aim(&self) -> i64 {
+        0
+    }
+
+    fn follow(&mut self, course: &[Command]) -> Result<(), CourseError> {
+        for &command in course {
+            self.apply(command)?;
+        }
+
+        Ok(())
+    }
+}
+
 pub struct Submarine {
-    pub horizontal_position: i32,
-    pub depth: i32,
+    pub horizontal_position: i64,
+    pub depth: i64,
 }
 
 pub struct SubmarineV2 {
-    pub horizontal_position: i32,
-    pub depth: i32,
-    pub aim: i32,
+    pub horizontal_position: i64,
+    pub depth: i64,
+    pub aim: i64,
 }
 
 impl Default for Submarine {
@@ -89,31 +464,27 @@ impl Submarine {
         }
     }
 
-    pub fn move_submarine(&mut self, instruction: &str) -> Result<&Submarine, &str> {
-        let split_instruction: Vec<&str> = instruction.split(' ').collect();
-
-        if split_instruction.len() < 2 {
-            return Err("missing value or command in submarine instruction");
-        }
-
-        let instruction_value = split_instruction[1]
-            .parse::<i32>()
-            .expect("unsupported submarine instruction value");
+    pub fn move_submarine(&mut self, command: Command) -> Result<&Submarine, CourseError> {
+        let (horizontal_position, depth) = match command {
+            Command::Forward(value) => (
+                self.horizontal_position.checked_add(value),
+                Some(self.depth),
+            ),
+            Command::Up(value) => (
+                Some(self.horizontal_position),
+                self.depth.checked_sub(value),
+            ),
+            Command::Down(value) => (
+                Some(self.horizontal_position),
+                self.depth.checked_add(value),
+            ),
+        };
 
-        match split_instruction[0] as &str {
-            "forward" => {
-                self.horizontal_position += instruction_value;
-            }
-            "up" => {
-                self.depth -= instruction_value;
-            }
-            "down" => {
-                self.depth += instruction_value;
-            }
-            _ => {
-                return Err("unsupported submarine instruction command");
-            }
-        }
+        // Nothing moves unless every coordinate fits.
+        let horizontal_position = horizontal_position.ok_or(CourseError::Overflow)?;
+        let depth = depth.ok_or(CourseError::Overflow)?;
+        self.horizontal_position = horizontal_position;
+        self.depth = depth;
 
         Ok(self)
     }
@@ -138,73 +509,799 @@ impl SubmarineV2 {
         }
     }
 
-    pub fn move_submarine(&mut self, instruction: &str) -> Result<&SubmarineV2, &str> {
-        let split_instruction: Vec<&str> = instruction.split(' ').collect();
+    pub fn move_submarine(&mut self, command: Command) -> Result<&SubmarineV2, CourseError> {
+        let (horizontal_position, depth, aim) = match command {
+            Command::Forward(value) => (
+                self.horizontal_position.checked_add(value),
+                self.aim
+                    .checked_mul(value)
+                    .and_then(|change| self.depth.checked_add(change)),
+                Some(self.aim),
+            ),
+            Command::Up(value) => (
+                Some(self.horizontal_position),
+                Some(self.depth),
+                self.aim.checked_sub(value),
+            ),
+            Command::Down(value) => (
+                Some(self.horizontal_position),
+                Some(self.depth),
+                self.aim.checked_add(value),
+            ),
+        };
+
+        // Nothing moves unless every coordinate fits.
+        let horizontal_position = horizontal_position.ok_or(CourseError::Overflow)?;
+        let depth = depth.ok_or(CourseError::Overflow)?;
+        let aim = aim.ok_or(CourseError::Overflow)?;
+        self.horizontal_position = horizontal_position;
+        self.depth = depth;
+        self.aim = aim;
+
+        Ok(self)
+    }
+}
+
+impl Navigator for Submarine {
+    fn apply(&mut self, command: Command) -> Result<(), CourseError> {
+        self.move_submarine(command).map(|_| ())
+    }
+
+    fn horizontal_position(&self) -> i64 {
+        self.horizontal_position
+    }
+
+    fn depth(&self) -> i64 {
+        self.depth
+    }
+}
+
+impl Navigator for SubmarineV2 {
+    fn apply(&mut self, command: Command) -> Result<(), CourseError> {
+        self.move_submarine(command).map(|_| ())
+    }
+
+    fn horizontal_position(&self) -> i64 {
+        self.horizontal_position
+    }
+
+    fn depth(&self) -> i64 {
+        self.depth
+    }
+
+    fn aim(&self) -> i64 {
+        self.aim
+    }
+}
+
+/// Submarine steering with an aim that loses `drag` percent of its value
+/// (rounded toward 0, from 0 to 100) after each move forward.
+pub struct SubmarineWithDrag {
+    pub submarine: SubmarineV2,
+    pub drag: i64,
+}
+
+impl SubmarineWithDrag {
+    pub fn new(drag: i64) -> SubmarineWithDrag {
+        SubmarineWithDrag {
+            submarine: SubmarineV2::new(),
+            drag,
+        }
+    }
+}
+
+impl Navigator for SubmarineWithDrag {
+    fn apply(&mut self, command: Command) -> Result<(), CourseError> {
+        let submarine = &mut self.submarine;
+
+        if !(0..=100).contains(&self.drag) {
+            return Err(CourseError::Drag(self.drag));
+        }
+        let aim = match command {
+            Command::Forward(_) => submarine
+                .aim
+                .checked_mul(self.drag)
+                .and_then(|loss| submarine.aim.checked_sub(loss / 100))
+                .ok_or(CourseError::Overflow)?,
+            Command::Up(_) | Command::Down(_) => submarine.aim,
+        };
+        submarine.move_submarine(command)?;
+        if let Command::Forward(_) = command {
+            submarine.aim = aim;
+        }
+
+        Ok(())
+    }
+
+    fn horizontal_position(&self) -> i64 {
+        self.submarine.horizontal_position
+    }
+
+    fn depth(&self) -> i64 {
+        self.submarine.depth
+    }
+
+    fn aim(&self) -> i64 {
+        self.submarine.aim
+    }
+}
+
+/// Submarine steering with an aim that cannot go above the surface nor below
+/// `max_depth`. It levels off (its aim goes back to 0) when going past either.
+pub struct DepthLimitedSubmarine {
+    pub submarine: SubmarineV2,
+    pub max_depth: i64,
+}
+
+impl DepthLimitedSubmarine {
+    pub fn new(max_depth: i64) -> DepthLimitedSubmarine {
+        DepthLimitedSubmarine {
+            submarine: SubmarineV2::new(),
+            max_depth,
+        }
+    }
+}
 
-        if split_instruction.len() < 2 {
-            return Err("missing value or command in submarine v2 instruction");
+impl Navigator for DepthLimitedSubmarine {
+    fn apply(&mut self, command: Command) -> Result<(), CourseError> {
+        let submarine = &mut self.submarine;
+        submarine.move_submarine(command)?;
+
+        if submarine.depth < 0 || submarine.depth > self.max_depth {
+            submarine.depth = submarine.depth.clamp(0, self.max_depth);
+            submarine.aim = 0;
+        }
+
+        Ok(())
+    }
+
+    fn horizontal_position(&self) -> i64 {
+        self.submarine.horizontal_position
+    }
+
+    fn depth(&self) -> i64 {
+        self.submarine.depth
+    }
+
+    fn aim(&self) -> i64 {
+        self.submarine.aim
+    }
+}
+
+/// State of a submarine after a command of its course.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub command: Command,
+    pub horizontal_position: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+/// Steps of a submarine following a course, from its start at position and
+/// depth 0.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trajectory {
+    pub steps: Vec<Step>,
+}
+
+const SVG_WIDTH: f64 = 800.0;
+const SVG_HEIGHT: f64 = 400.0;
+const SVG_MARGIN: f64 = 40.0;
+
+impl Trajectory {
+    pub fn record(
+        navigator: &mut dyn Navigator,
+        course: &[Command],
+    ) -> Result<Trajectory, CourseError> {
+        let steps = course
+            .iter()
+            .map(|&command| {
+                navigator.apply(command)?;
+
+                Ok(Step {
+                    command,
+                    horizontal_position: navigator.horizontal_position(),
+                    depth: navigator.depth(),
+                    aim: navigator.aim(),
+                })
+            })
+            .collect::<Result<Vec<Step>, CourseError>>()?;
+
+        Ok(Trajectory { steps })
+    }
+
+    /// Index of the first step reaching the maximum depth, with that step.
+    pub fn deepest(&self) -> Option<(usize, &Step)> {
+        self.steps
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, step)| step.depth)
+    }
+
+    /// One line per step, after a header line.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step,command,horizontal_position,depth,aim\n");
+
+        for (index, step) in self.steps.iter().enumerate() {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                index + 1,
+                step.command,
+                step.horizontal_position,
+                step.depth,
+                step.aim
+            ));
+        }
+
+        csv
+    }
+
+    /// Plot of the depth against the horizontal position, deeper going down,
+    /// with the deepest point marked.
+    pub fn to_svg(&self) -> String {
+        let points: Vec<(i64, i64)> = [(0, 0)]
+            .into_iter()
+            .chain(
+                self.steps
+                    .iter()
+                    .map(|step| (step.horizontal_position, step.depth)),
+            )
+            .collect();
+        let bounds = |values: Vec<i64>| {
+            let min = values.iter().copied().min().unwrap_or(0);
+            let max = values.iter().copied().max().unwrap_or(0);
+            (min, max)
+        };
+        let (min_x, max_x) = bounds(points.iter().map(|&(x, _)| x).collect());
+        let (min_y, max_y) = bounds(points.iter().map(|&(_, y)| y).collect());
+        let scale = |value: i64, min: i64, max: i64, length: f64| {
+            let (value, min, max) = (i128::from(value), i128::from(min), i128::from(max));
+            SVG_MARGIN + (value - min) as f64 / (max - min).max(1) as f64 * length
+        };
+        let x = |value| scale(value, min_x, max_x, SVG_WIDTH - 2.0 * SVG_MARGIN);
+        let y = |value| scale(value, min_y, max_y, SVG_HEIGHT - 2.0 * SVG_MARGIN);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+viewBox=\"0 0 {0} {1}\">\n",
+            SVG_WIDTH, SVG_HEIGHT
+        );
+        svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n");
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"14\">\
+Depth from {} to {}, horizontal position from {} to {}</text>\n",
+            SVG_MARGIN,
+            SVG_MARGIN / 2.0,
+            min_y,
+            max_y,
+            min_x,
+            max_x
+        ));
+        svg.push_str(&format!(
+            "<line x1=\"{:.1}\" y1=\"{2:.1}\" x2=\"{:.1}\" y2=\"{2:.1}\" stroke=\"gray\" \
+stroke-dasharray=\"4\"/>\n",
+            x(min_x),
+            x(max_x),
+            y(0)
+        ));
+        svg.push_str(&format!(
+            "<polyline fill=\"none\" stroke=\"steelblue\" stroke-width=\"2\" points=\"{}\"/>\n",
+            points
+                .iter()
+                .map(|&(point_x, point_y)| format!("{:.1},{:.1}", x(point_x), y(point_y)))
+                .collect::<Vec<String>>()
+                .join(" ")
+        ));
+        if let Some((index, step)) = self.deepest() {
+            svg.push_str(&format!(
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" fill=\"red\"><title>\
+Depth {} after command {} ({})</title></circle>\n",
+                x(step.horizontal_position),
+                y(step.depth),
+                step.depth,
+                index + 1,
+                step.command
+            ));
+        }
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+}
+
+/// How the commands steer a submarine: `Simple` like [`Submarine`] and `Aim`
+/// like [`SubmarineV2`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    Simple,
+    Aim,
+}
+
+impl Model {
+    pub fn navigator(self) -> Box<dyn Navigator> {
+        match self {
+            Model::Simple => Box::new(Submarine::new()),
+            Model::Aim => Box::new(SubmarineV2::new()),
         }
+    }
+}
+
+/// Change of aim when `remaining` units of the course are left to move forward.
+struct Turn {
+    remaining: i64,
+    aim_change: i64,
+}
+
+/// Number of course shapes the aim model planner may try while searching for a
+/// minimal course.
+const MAX_PLANNER_ATTEMPTS: usize = 10_000_000;
+
+const UNREACHABLE: &str = "The target cannot be reached within the maximum number of commands";
+
+/// Planner of minimal courses reaching a target, with positive command values.
+///
+/// With a `max_value`, the aim model searches the courses changing the aim at
+/// most three times for one shorter than the ones changing it once or twice,
+/// and only fails when that search is too long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Planner {
+    pub model: Model,
+    pub max_commands: Option<usize>,
+    pub max_value: Option<i64>,
+}
+
+/// Number of commands of at most `max_value` adding up to `value`.
+fn command_count(value: i64, max_value: i64) -> i64 {
+    value / max_value + i64::from(value % max_value != 0)
+}
+
+/// Number of commands of the course following `turns`.
+fn course_length(turns: &[Turn], horizontal_position: i64, max_value: i64) -> i64 {
+    let mut remaining = horizontal_position;
+    let mut length: i64 = 0;
 
-        let instruction_value = split_instruction[1]
-            .parse::<i32>()
-            .expect("unsupported submarine v2 instruction value");
+    for turn in turns {
+        length = length
+            .saturating_add(command_count(remaining - turn.remaining, max_value))
+            .saturating_add(command_count(turn.aim_change.abs(), max_value));
+        remaining = turn.remaining;
+    }
+
+    length.saturating_add(command_count(remaining, max_value))
+}
 
-        match split_instruction[0] as &str {
-            "forward" => {
-                self.horizontal_position += instruction_value;
-                self.depth += self.aim * instruction_value;
+/// Turns to `final_aim`, in the direction of `sign`, with the aim changes of
+/// `later_turns` left for when the submarine moves forward to their positions.
+fn shape_turns(
+    horizontal_position: i64,
+    sign: i64,
+    final_aim: i128,
+    later_turns: &[(i128, i128)],
+) -> Option<Vec<Turn>> {
+    let first_turn = final_aim - later_turns.iter().map(|&(_, turn)| turn).sum::<i128>();
+    let mut turns = vec![Turn {
+        remaining: horizontal_position,
+        aim_change: i64::try_from(first_turn).ok()? * sign,
+    }];
+
+    for &(position, turn) in later_turns {
+        turns.push(Turn {
+            remaining: horizontal_position - i64::try_from(position).ok()?,
+            aim_change: i64::try_from(turn).ok()? * sign,
+        });
+    }
+
+    Some(turns)
+}
+
+/// Course following `turns`.
+fn follow_turns(turns: &[Turn], horizontal_position: i64, max_value: i64) -> Vec<Command> {
+    let mut course = Vec::new();
+    let mut remaining = horizontal_position;
+
+    for turn in turns {
+        push_commands(
+            &mut course,
+            remaining - turn.remaining,
+            max_value,
+            Command::Forward,
+        );
+        push_aim_change(&mut course, turn.aim_change, max_value);
+        remaining = turn.remaining;
+    }
+    push_commands(&mut course, remaining, max_value, Command::Forward);
+
+    course
+}
+
+fn push_commands(
+    course: &mut Vec<Command>,
+    value: i64,
+    max_value: i64,
+    command: fn(i64) -> Command,
+) {
+    let mut left = value;
+
+    while left > 0 {
+        let value = left.min(max_value);
+        course.push(command(value));
+        left -= value;
+    }
+}
+
+fn push_aim_change(course: &mut Vec<Command>, aim_change: i64, max_value: i64) {
+    match aim_change < 0 {
+        true => push_commands(course, -aim_change, max_value, Command::Up),
+        false => push_commands(course, aim_change, max_value, Command::Down),
+    }
+}
+
+impl Planner {
+    pub fn new(model: Model) -> Planner {
+        Planner {
+            model,
+            max_commands: None,
+            max_value: None,
+        }
+    }
+
+    /// Course ending at `horizontal_position` and `depth`.
+    pub fn plan(&self, horizontal_position: i64, depth: i64) -> Result<Vec<Command>, &'static str> {
+        let max_value = self.max_value.unwrap_or(i64::MAX);
+        let max_commands = self.max_commands.map_or(i64::MAX, |max_commands| {
+            i64::try_from(max_commands).unwrap_or(i64::MAX)
+        });
+
+        if max_value < 1 {
+            return Err("The maximum value of a command must be positive");
+        }
+        if horizontal_position < 0 {
+            return Err("The submarine cannot move backward");
+        }
+        if depth == i64::MIN {
+            return Err("The target depth is out of range");
+        }
+
+        match self.model {
+            // The simple model moves forward, then changes depth once at the end.
+            Model::Simple => {
+                let turns = [Turn {
+                    remaining: 0,
+                    aim_change: depth,
+                }];
+
+                match course_length(&turns, horizontal_position, max_value) > max_commands {
+                    true => Err(UNREACHABLE),
+                    false => Ok(follow_turns(&turns, horizontal_position, max_value)),
+                }
+            }
+            Model::Aim => self.plan_aim(horizontal_position, depth, max_value, max_commands),
+        }
+    }
+
+    /// Shortest course of the aim model reaching `depth` by moving
+    /// `horizontal_position` units forward.
+    fn plan_aim(
+        &self,
+        horizontal_position: i64,
+        depth: i64,
+        max_value: i64,
+        max_commands: i64,
+    ) -> Result<Vec<Command>, &'static str> {
+        if depth == 0 {
+            return match command_count(horizontal_position, max_value) > max_commands {
+                true => Err(UNREACHABLE),
+                false => Ok(follow_turns(&[], horizontal_position, max_value)),
+            };
+        }
+        if horizontal_position == 0 {
+            return Err("The submarine cannot change depth without moving forward");
+        }
+
+        // Every course moves forward the whole distance, and changes the aim
+        // enough to reach the depth even if it turned at the start.
+        let lower_bound = command_count(horizontal_position, max_value).saturating_add(
+            command_count(depth.abs(), horizontal_position.saturating_mul(max_value)),
+        );
+        if lower_bound > max_commands {
+            return Err(UNREACHABLE);
+        }
+
+        let mut candidates = Vec::new();
+        if depth % horizontal_position == 0 {
+            candidates.push(vec![Turn {
+                remaining: horizontal_position,
+                aim_change: depth / horizontal_position,
+            }]);
+        }
+        candidates.push(vec![Turn {
+            remaining: 1,
+            aim_change: depth,
+        }]);
+        let aim = depth.div_euclid(horizontal_position);
+        let rest = depth.rem_euclid(horizontal_position);
+        if rest != 0 {
+            candidates.push(vec![
+                Turn {
+                    remaining: horizontal_position,
+                    aim_change: aim,
+                },
+                Turn {
+                    remaining: rest,
+                    aim_change: 1,
+                },
+            ]);
+            candidates.push(vec![
+                Turn {
+                    remaining: horizontal_position,
+                    aim_change: aim + 1,
+                },
+                Turn {
+                    remaining: horizontal_position - rest,
+                    aim_change: -1,
+                },
+            ]);
+        }
+        let best = candidates
+            .iter()
+            .min_by_key(|turns| course_length(turns, horizontal_position, max_value))
+            .expect("there is always a candidate");
+        let best_length = course_length(best, horizontal_position, max_value);
+
+        // Two commands only turn at the start, so without a limit on their
+        // values, the candidates turning once are already minimal.
+        let unlimited = max_value >= horizontal_position && max_value >= depth.abs();
+        if unlimited && best_length > max_commands {
+            return Err(UNREACHABLE);
+        }
+        if best_length == lower_bound || unlimited {
+            return Ok(follow_turns(best, horizontal_position, max_value));
+        }
+
+        let budget = (best_length - 1).min(max_commands);
+        match Planner::search(horizontal_position, depth, max_value, budget)? {
+            Some(course) => Ok(course),
+            None if best_length <= max_commands => {
+                Ok(follow_turns(best, horizontal_position, max_value))
+            }
+            None => Err(UNREACHABLE),
+        }
+    }
+
+    /// Shortest course of at most `budget` commands reaching `depth` at
+    /// `horizontal_position`, among the courses turning at the start, possibly
+    /// away from the target, then at most twice more toward it.
+    ///
+    /// Such a course turns to a final aim, and misses the depth it would reach
+    /// with that aim from the start by the deficit of its later turns, each
+    /// one the aim change times the distance moved before it.
+    fn search(
+        horizontal_position: i64,
+        depth: i64,
+        max_value: i64,
+        budget: i64,
+    ) -> Result<Option<Vec<Command>>, &'static str> {
+        let distance = i128::from(horizontal_position);
+        let value = i128::from(max_value);
+        let target = i128::from(depth).abs();
+        let forward_commands = command_count(horizontal_position, max_value);
+        let mut best: Option<(i64, Vec<Turn>)> = None;
+        let mut attempts = 0;
+        let mut attempt = || {
+            attempts += 1;
+            match attempts > MAX_PLANNER_ATTEMPTS {
+                true => Err("The target is too far to search for a minimal course"),
+                false => Ok(()),
+            }
+        };
+
+        let mut final_aim = (target + distance - 1) / distance;
+        loop {
+            let limit = best.as_ref().map_or(budget, |(length, _)| length - 1);
+            let aim_commands = (final_aim + value - 1) / value;
+            let slack = i128::from(limit - forward_commands) - aim_commands;
+            if slack < 0 {
+                break;
             }
-            "up" => {
-                self.aim -= instruction_value;
+            attempt()?;
+
+            // Without slack, a course cannot afford any command below the
+            // maximum value that the distance or the final aim does not need.
+            let deficit = final_aim * distance - target;
+            let aligned_positions = slack == 0 && distance % value == 0;
+            let aligned_turns = slack == 0 && final_aim % value == 0;
+            let position_step = if aligned_positions { value } else { 1 };
+            let turn_step = if aligned_turns { value } else { 1 };
+            let mut shapes = Vec::new();
+
+            if deficit == 0 {
+                shapes.push(vec![]);
             }
-            "down" => {
-                self.aim += instruction_value;
+            if deficit % position_step == 0 && deficit % turn_step == 0 {
+                let mut position = position_step;
+                while position < distance.min(deficit + 1) {
+                    attempt()?;
+                    let turn = deficit / position;
+                    if deficit % position == 0 && turn % turn_step == 0 {
+                        shapes.push(vec![(position, turn)]);
+                    }
+                    position += position_step;
+                }
+
+                // The turns of two later changes of aim stay below the maximum
+                // value in a minimal course.
+                let mut first = position_step;
+                while first < distance && first < deficit {
+                    let mut first_turn = turn_step;
+                    while first_turn <= value && first_turn * first < deficit {
+                        let left = deficit - first_turn * first;
+                        let mut second_turn = turn_step;
+                        while second_turn <= value && second_turn * (first + 1) <= left {
+                            attempt()?;
+                            let second = left / second_turn;
+                            if left % second_turn == 0
+                                && second < distance
+                                && second % position_step == 0
+                            {
+                                shapes.push(vec![(first, first_turn), (second, second_turn)]);
+                            }
+                            second_turn += turn_step;
+                        }
+                        first_turn += turn_step;
+                    }
+                    first += position_step;
+                }
             }
-            _ => {
-                return Err("unsupported submarine v2 instruction command");
+
+            for shape in shapes {
+                let Some(turns) =
+                    shape_turns(horizontal_position, depth.signum(), final_aim, &shape)
+                else {
+                    continue;
+                };
+                let length = course_length(&turns, horizontal_position, max_value);
+                if length <= best.as_ref().map_or(budget, |(length, _)| length - 1) {
+                    best = Some((length, turns));
+                }
             }
+            final_aim += 1;
         }
 
-        Ok(self)
+        Ok(best.map(|(_, turns)| follow_turns(&turns, horizontal_position, max_value)))
     }
-}
 
-pub fn part1(input: &str) -> i32 {
-    let instructions = input.split('\n');
-    let mut submarine = Submarine::new();
+    /// Whether `course` respects the limits of the planner and ends at
+    /// `horizontal_position` and `depth`, replayed by the submarine of the model.
+    pub fn verify(&self, course: &[Command], horizontal_position: i64, depth: i64) -> bool {
+        let max_value = self.max_value.unwrap_or(i64::MAX);
+        let mut navigator = self.model.navigator();
+
+        if self
+            .max_commands
+            .is_some_and(|max_commands| course.len() > max_commands)
+        {
+            return false;
+        }
+        if course.iter().any(|command| match command {
+            Command::Forward(value) | Command::Up(value) | Command::Down(value) => {
+                !(1..=max_value).contains(value)
+            }
+        }) {
+            return false;
+        }
 
-    for instruction in instructions {
-        match submarine.move_submarine(instruction) {
-            Ok(submarine) => submarine,
-            Err(_) => continue,
-        };
+        navigator.follow(course).is_ok()
+            && (navigator.horizontal_position(), navigator.depth()) == (horizontal_position, depth)
     }
+}
+
+/// Horizontal position * depth at the end of the course of `input`, followed
+/// by `navigator`.
+pub fn navigate(navigator: &mut dyn Navigator, input: &str) -> Result<i64, CourseError> {
+    navigator.follow(&parse_course(input)?)?;
+
+    navigator
+        .horizontal_position()
+        .checked_mul(navigator.depth())
+        .ok_or(CourseError::Overflow)
+}
+
+pub fn part1(input: &str) -> Result<i64, CourseError> {
+    navigate(&mut Submarine::new(), input)
+}
 
-    submarine.horizontal_position * submarine.depth
+pub fn part2(input: &str) -> Result<i64, CourseError> {
+    navigate(&mut SubmarineV2::new(), input)
 }
 
-pub fn part2(input: &str) -> i32 {
-    let instructions = input.split('\n');
-    let mut submarine = SubmarineV2::new();
+pub struct Explanation;
+
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        let solver_error = |error: CourseError| AocError::Solver(error.to_string());
+        let course = parse_course(input).map_err(|error| solver_error(error.into()))?;
+        let mut lines = vec![format!("The course has {} commands", course.len())];
+
+        let mut navigator: Box<dyn Navigator> = match part {
+            1 => Box::new(Submarine::new()),
+            _ => Box::new(SubmarineV2::new()),
+        };
+
+        let trajectory = Trajectory::record(navigator.as_mut(), &course).map_err(solver_error)?;
+        if let Some((index, step)) = trajectory.deepest() {
+            lines.push(format!(
+                "The deepest point is at depth {}, reached by command {} ({})",
+                step.depth,
+                index + 1,
+                step.command
+            ));
+        }
+        if part == 2 {
+            lines.push(format!("The final aim is {}", navigator.aim()));
+        }
+
+        let (horizontal_position, depth) = (navigator.horizontal_position(), navigator.depth());
+        lines.insert(
+            1,
+            format!(
+                "The submarine ends at horizontal position {} and depth {}",
+                horizontal_position, depth
+            ),
+        );
+        lines.push(format!(
+            "{} * {} = {}",
+            horizontal_position,
+            depth,
+            horizontal_position
+                .checked_mul(depth)
+                .ok_or(CourseError::Overflow)
+                .map_err(solver_error)?
+        ));
+
+        Ok(lines)
+    }
+}
 
-    for instruction in instructions {
-        match submarine.move_submarine(instruction) {
-            Ok(submarine) => submarine,
-            Err(_) => continue,
+/// Random course of `size` commands, never going above the surface.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut aim = 0;
+    let mut course = String::new();
+
+    for _ in 0..size {
+        let value = rng.range(1, 9);
+        let command = match rng.below(3) {
+            0 => "forward",
+            1 if aim >= value => {
+                aim -= value;
+                "up"
+            }
+            _ => {
+                aim += value;
+                "down"
+            }
         };
+
+        course.push_str(&format!("{} {}\n", command, value));
     }
 
-    submarine.horizontal_position * submarine.depth
+    course
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day2::{part1, part2, Submarine, SubmarineV2};
+    use crate::day2::{
+        generate, navigate, parse_course, part1, part2, Command, CommandError, CourseError,
+        DepthLimitedSubmarine, Model, Navigator, Planner, Step, Submarine, SubmarineV2,
+        SubmarineWithDrag, Trajectory,
+    };
+    use crate::rng::Rng;
+    use std::collections::HashMap;
 
     #[test]
     fn test_submarine_foward5() {
         let mut submarine = Submarine::new();
-        submarine.move_submarine("forward 5").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 5);
         assert_eq!(submarine.depth, 0);
@@ -213,8 +1310,8 @@ mod tests {
     #[test]
     fn test_submarine_foward5_down5() {
         let mut submarine = Submarine::new();
-        submarine.move_submarine("forward 5").unwrap();
-        submarine.move_submarine("down 5").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
+        submarine.move_submarine(Command::Down(5)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 5);
         assert_eq!(submarine.depth, 5);
@@ -223,9 +1320,9 @@ mod tests {
     #[test]
     fn test_submarine_foward5_down5_forward8() {
         let mut submarine = Submarine::new();
-        submarine.move_submarine("forward 5").unwrap();
-        submarine.move_submarine("down 5").unwrap();
-        submarine.move_submarine("forward 8").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
+        submarine.move_submarine(Command::Down(5)).unwrap();
+        submarine.move_submarine(Command::Forward(8)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 13);
         assert_eq!(submarine.depth, 5);
@@ -234,10 +1331,10 @@ mod tests {
     #[test]
     fn test_submarine_foward5_down5_forward8_up3() {
         let mut submarine = Submarine::new();
-        submarine.move_submarine("forward 5").unwrap();
-        submarine.move_submarine("down 5").unwrap();
-        submarine.move_submarine("forward 8").unwrap();
-        submarine.move_submarine("up 3").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
+        submarine.move_submarine(Command::Down(5)).unwrap();
+        submarine.move_submarine(Command::Forward(8)).unwrap();
+        submarine.move_submarine(Command::Up(3)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 13);
         assert_eq!(submarine.depth, 2);
@@ -246,11 +1343,11 @@ mod tests {
     #[test]
     fn test_submarine_foward5_down5_forward8_up3_down8() {
         let mut submarine = Submarine::new();
-        submarine.move_submarine("forward 5").unwrap();
-        submarine.move_submarine("down 5").unwrap();
-        submarine.move_submarine("forward 8").unwrap();
-        submarine.move_submarine("up 3").unwrap();
-        submarine.move_submarine("down 8").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
+        submarine.move_submarine(Command::Down(5)).unwrap();
+        submarine.move_submarine(Command::Forward(8)).unwrap();
+        submarine.move_submarine(Command::Up(3)).unwrap();
+        submarine.move_submarine(Command::Down(8)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 13);
         assert_eq!(submarine.depth, 10);
@@ -259,29 +1356,80 @@ mod tests {
     #[test]
     fn test_submarine_foward5_down5_forward8_up3_down8_forward2() {
         let mut submarine = Submarine::new();
-        submarine.move_submarine("forward 5").unwrap();
-        submarine.move_submarine("down 5").unwrap();
-        submarine.move_submarine("forward 8").unwrap();
-        submarine.move_submarine("up 3").unwrap();
-        submarine.move_submarine("down 8").unwrap();
-        submarine.move_submarine("forward 2").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
+        submarine.move_submarine(Command::Down(5)).unwrap();
+        submarine.move_submarine(Command::Forward(8)).unwrap();
+        submarine.move_submarine(Command::Up(3)).unwrap();
+        submarine.move_submarine(Command::Down(8)).unwrap();
+        submarine.move_submarine(Command::Forward(2)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 15);
         assert_eq!(submarine.depth, 10);
     }
 
+    #[test]
+    fn test_command_from_str() {
+        let error = |column, message| CommandError {
+            line: 1,
+            column,
+            message,
+        };
+
+        assert_eq!("forward 5".parse(), Ok(Command::Forward(5)));
+        assert_eq!("up 3".parse(), Ok(Command::Up(3)));
+        assert_eq!("down 8".parse(), Ok(Command::Down(8)));
+        assert_eq!(
+            "forward x".parse::<Command>(),
+            Err(error(9, "unsupported submarine command value"))
+        );
+        assert_eq!(
+            "down".parse::<Command>(),
+            Err(error(5, "missing submarine command value"))
+        );
+        assert_eq!(
+            "dive 2".parse::<Command>(),
+            Err(error(1, "unsupported submarine command"))
+        );
+        assert_eq!(Command::Up(3).to_string(), "up 3");
+    }
+
+    #[test]
+    fn test_parse_course() {
+        assert_eq!(
+            parse_course("forward 5\n\ndown 5\n"),
+            Ok(vec![Command::Forward(5), Command::Down(5)])
+        );
+
+        let error = parse_course("forward 5\ndown 5\nup three").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 4: unsupported submarine command value"
+        );
+
+        let course = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
+        assert_eq!(
+            parse_course(course)
+                .unwrap()
+                .iter()
+                .map(|command| command.to_string())
+                .collect::<Vec<String>>()
+                .join("\n"),
+            course
+        );
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(
             part1("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2"),
-            150
+            Ok(150)
         )
     }
 
     #[test]
     fn test_submarinev2_foward5() {
         let mut submarine = SubmarineV2::new();
-        submarine.move_submarine("forward 5").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 5);
         assert_eq!(submarine.depth, 0);
@@ -291,8 +1439,8 @@ mod tests {
     #[test]
     fn test_submarinev2_foward5_down5() {
         let mut submarine = SubmarineV2::new();
-        submarine.move_submarine("forward 5").unwrap();
-        submarine.move_submarine("down 5").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
+        submarine.move_submarine(Command::Down(5)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 5);
         assert_eq!(submarine.depth, 0);
@@ -302,9 +1450,9 @@ mod tests {
     #[test]
     fn test_submarinev2_foward5_down5_forward8() {
         let mut submarine = SubmarineV2::new();
-        submarine.move_submarine("forward 5").unwrap();
-        submarine.move_submarine("down 5").unwrap();
-        submarine.move_submarine("forward 8").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
+        submarine.move_submarine(Command::Down(5)).unwrap();
+        submarine.move_submarine(Command::Forward(8)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 13);
         assert_eq!(submarine.depth, 40);
@@ -314,10 +1462,10 @@ mod tests {
     #[test]
     fn test_submarinev2_foward5_down5_forward8_up3() {
         let mut submarine = SubmarineV2::new();
-        submarine.move_submarine("forward 5").unwrap();
-        submarine.move_submarine("down 5").unwrap();
-        submarine.move_submarine("forward 8").unwrap();
-        submarine.move_submarine("up 3").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
+        submarine.move_submarine(Command::Down(5)).unwrap();
+        submarine.move_submarine(Command::Forward(8)).unwrap();
+        submarine.move_submarine(Command::Up(3)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 13);
         assert_eq!(submarine.depth, 40);
@@ -327,11 +1475,11 @@ mod tests {
     #[test]
     fn test_submarinev2_foward5_down5_forward8_up3_down8() {
         let mut submarine = SubmarineV2::new();
-        submarine.move_submarine("forward 5").unwrap();
-        submarine.move_submarine("down 5").unwrap();
-        submarine.move_submarine("forward 8").unwrap();
-        submarine.move_submarine("up 3").unwrap();
-        submarine.move_submarine("down 8").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
+        submarine.move_submarine(Command::Down(5)).unwrap();
+        submarine.move_submarine(Command::Forward(8)).unwrap();
+        submarine.move_submarine(Command::Up(3)).unwrap();
+        submarine.move_submarine(Command::Down(8)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 13);
         assert_eq!(submarine.depth, 40);
@@ -341,12 +1489,12 @@ mod tests {
     #[test]
     fn test_submarinev2_foward5_down5_forward8_up3_down8_forward2() {
         let mut submarine = SubmarineV2::new();
-        submarine.move_submarine("forward 5").unwrap();
-        submarine.move_submarine("down 5").unwrap();
-        submarine.move_submarine("forward 8").unwrap();
-        submarine.move_submarine("up 3").unwrap();
-        submarine.move_submarine("down 8").unwrap();
-        submarine.move_submarine("forward 2").unwrap();
+        submarine.move_submarine(Command::Forward(5)).unwrap();
+        submarine.move_submarine(Command::Down(5)).unwrap();
+        submarine.move_submarine(Command::Forward(8)).unwrap();
+        submarine.move_submarine(Command::Up(3)).unwrap();
+        submarine.move_submarine(Command::Down(8)).unwrap();
+        submarine.move_submarine(Command::Forward(2)).unwrap();
 
         assert_eq!(submarine.horizontal_position, 15);
         assert_eq!(submarine.depth, 60);
@@ -357,7 +1505,475 @@ mod tests {
     fn test_part2() {
         assert_eq!(
             part2("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2"),
-            900
+            Ok(900)
         )
     }
+
+    #[test]
+    fn test_navigator() {
+        let course = parse_course("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2").unwrap();
+        let mut submarine = Submarine::new();
+        let mut submarine_v2 = SubmarineV2::new();
+
+        submarine.follow(&course).unwrap();
+        submarine_v2.follow(&course).unwrap();
+
+        assert_eq!(
+            (submarine.horizontal_position(), submarine.depth()),
+            (15, 10)
+        );
+        assert_eq!(submarine.aim(), 0);
+        assert_eq!(
+            (submarine_v2.horizontal_position(), submarine_v2.depth()),
+            (15, 60)
+        );
+        assert_eq!(submarine_v2.aim(), 10);
+    }
+
+    #[test]
+    fn test_submarine_with_drag() {
+        let input = "down 10\nforward 1\nforward 1\nforward 1";
+
+        assert_eq!(
+            navigate(&mut SubmarineWithDrag::new(0), input),
+            part2(input)
+        );
+        // The aim goes from 10 to 5, 3 (5 - 2.5 rounded toward 0) and 2.
+        let mut submarine = SubmarineWithDrag::new(50);
+        assert_eq!(navigate(&mut submarine, input), Ok(3 * 18));
+        assert_eq!(submarine.aim(), 2);
+
+        for drag in [-1, 101] {
+            let mut submarine = SubmarineWithDrag::new(drag);
+            assert_eq!(
+                navigate(&mut submarine, input),
+                Err(CourseError::Drag(drag))
+            );
+            assert_eq!(submarine.horizontal_position(), 0);
+        }
+        assert_eq!(
+            CourseError::Drag(101).to_string(),
+            "the drag of 101 percent is not between 0 and 100"
+        );
+    }
+
+    #[test]
+    fn test_depth_limited_submarine() {
+        let mut submarine = DepthLimitedSubmarine::new(12);
+
+        submarine
+            .follow(&parse_course("down 5\nforward 2\nforward 2\nforward 1").unwrap())
+            .unwrap();
+        assert_eq!((submarine.depth(), submarine.aim()), (12, 0));
+
+        submarine
+            .follow(&parse_course("up 20\nforward 1\ndown 1\nforward 1").unwrap())
+            .unwrap();
+        assert_eq!((submarine.horizontal_position(), submarine.depth()), (7, 1));
+        assert_eq!(submarine.aim(), 1);
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let course = parse_course("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2").unwrap();
+        let trajectory = Trajectory::record(&mut SubmarineV2::new(), &course).unwrap();
+
+        assert_eq!(trajectory.steps.len(), 6);
+        assert_eq!(
+            trajectory.steps[2],
+            Step {
+                command: Command::Forward(8),
+                horizontal_position: 13,
+                depth: 40,
+                aim: 5
+            }
+        );
+        assert_eq!(trajectory.deepest().map(|(index, _)| index), Some(5));
+        assert_eq!(
+            Trajectory::record(&mut Submarine::new(), &course)
+                .unwrap()
+                .deepest()
+                .map(|(index, step)| (index, step.depth)),
+            Some((4, 10))
+        );
+        assert_eq!(Trajectory::default().deepest(), None);
+    }
+
+    #[test]
+    fn test_trajectory_csv() {
+        let course = parse_course("forward 5\ndown 5\nforward 8").unwrap();
+
+        assert_eq!(
+            Trajectory::record(&mut SubmarineV2::new(), &course)
+                .unwrap()
+                .to_csv(),
+            "step,command,horizontal_position,depth,aim\n\
+1,forward 5,5,0,0\n\
+2,down 5,5,0,5\n\
+3,forward 8,13,40,5\n"
+        );
+    }
+
+    #[test]
+    fn test_trajectory_svg() {
+        let course = parse_course("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2").unwrap();
+        let svg = Trajectory::record(&mut SubmarineV2::new(), &course)
+            .unwrap()
+            .to_svg();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("points=\"40.0,40.0 280.0,40.0 280.0,40.0 664.0,253.3 "));
+        assert!(svg.contains("<title>Depth 60 after command 6 (forward 2)</title>"));
+
+        let course = parse_course(&format!("up {0}\ndown {0}\ndown {0}", i64::MAX)).unwrap();
+        let svg = Trajectory::record(&mut Submarine::new(), &course)
+            .unwrap()
+            .to_svg();
+        assert!(svg.contains("points=\"40.0,200.0 40.0,40.0 40.0,200.0 40.0,360.0\""));
+
+        let empty = Trajectory::default().to_svg();
+        assert!(empty.contains("points=\"40.0,40.0\""));
+        assert!(!empty.contains("<circle"));
+    }
+
+    #[test]
+    fn test_planner_simple() {
+        let mut planner = Planner::new(Model::Simple);
+
+        assert_eq!(
+            planner.plan(15, 10),
+            Ok(vec![Command::Forward(15), Command::Down(10)])
+        );
+        assert_eq!(planner.plan(0, -3), Ok(vec![Command::Up(3)]));
+
+        planner.max_value = Some(6);
+        let course = planner.plan(15, 10).unwrap();
+        assert_eq!(course.len(), 5);
+        assert!(planner.verify(&course, 15, 10));
+        assert!(!planner.verify(&course, 15, 11));
+        assert!(!Planner::new(Model::Aim).verify(&course, 15, 10));
+
+        planner.max_commands = Some(4);
+        assert!(planner.plan(15, 10).is_err());
+        assert!(!planner.verify(&course, 15, 10));
+    }
+
+    #[test]
+    fn test_planner_aim() {
+        let mut planner = Planner::new(Model::Aim);
+
+        assert_eq!(
+            planner.plan(15, 60),
+            Ok(vec![Command::Down(4), Command::Forward(15)])
+        );
+        assert_eq!(
+            planner.plan(15, 61),
+            Ok(vec![
+                Command::Forward(14),
+                Command::Down(61),
+                Command::Forward(1)
+            ])
+        );
+        assert_eq!(planner.plan(0, 0), Ok(vec![]));
+        assert!(planner.plan(0, 5).is_err());
+        assert!(planner.plan(-1, 0).is_err());
+        assert!(planner.verify(
+            &parse_course("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2").unwrap(),
+            15,
+            60
+        ));
+
+        planner.max_value = Some(9);
+        let course = planner.plan(15, 900).unwrap();
+        assert_eq!(course.len(), 9);
+        assert!(planner.verify(&course, 15, 900));
+
+        let planner = Planner {
+            model: Model::Aim,
+            max_commands: Some(4),
+            max_value: Some(3),
+        };
+        let course = planner.plan(2, 9).unwrap();
+        assert_eq!(course.len(), 4);
+        assert!(planner.verify(&course, 2, 9));
+        assert_eq!(
+            planner.plan(2, 19),
+            Err("The target cannot be reached within the maximum number of commands")
+        );
+        let planner = Planner {
+            model: Model::Aim,
+            max_commands: Some(2),
+            max_value: None,
+        };
+        assert_eq!(
+            planner.plan(3, 5),
+            Err("The target cannot be reached within the maximum number of commands")
+        );
+
+        let mut planner = Planner::new(Model::Aim);
+        planner.max_value = Some(1_000_000);
+        assert_eq!(
+            planner
+                .plan(1_000_000_000_000, 1_000_000_000_000_000_000)
+                .map(|course| course.len()),
+            Ok(1_000_001)
+        );
+        let course = planner
+            .plan(1_000_000_000_000, 999_999_999_999_999_999)
+            .unwrap();
+        assert_eq!(course.len(), 1_000_003);
+        assert!(planner.verify(&course, 1_000_000_000_000, 999_999_999_999_999_999));
+
+        planner.max_value = Some(10);
+        for (horizontal_position, depth, length) in [(100, 12345, 24), (1000, 999_999, 202)] {
+            let course = planner.plan(horizontal_position, depth).unwrap();
+            assert_eq!(course.len(), length);
+            assert!(planner.verify(&course, horizontal_position, depth));
+        }
+
+        planner.max_value = Some(0);
+        assert!(planner.plan(15, 900).is_err());
+    }
+
+    #[test]
+    fn test_planner_aim_is_minimal() {
+        for max_value in 1..=5 {
+            // Fewest commands reaching each position and depth, by trying every
+            // course of up to 6 commands.
+            let mut fewest = HashMap::from([((0, 0), 0)]);
+            let mut layer = vec![(0, 0, 0)];
+            for length in 1..=6 {
+                let mut next = Vec::new();
+                for (position, aim, depth) in layer {
+                    for value in 1..=max_value {
+                        next.push((position, aim + value, depth));
+                        next.push((position, aim - value, depth));
+                        if position + value <= 9 {
+                            next.push((position + value, aim, depth + aim * value));
+                        }
+                    }
+                }
+                for &(position, _, depth) in &next {
+                    fewest.entry((position, depth)).or_insert(length);
+                }
+                next.sort_unstable();
+                next.dedup();
+                layer = next;
+            }
+
+            let mut planner = Planner::new(Model::Aim);
+            planner.max_value = Some(max_value);
+            for horizontal_position in 0..=9 {
+                for depth in -30..=30 {
+                    let plan = planner.plan(horizontal_position, depth);
+
+                    if horizontal_position == 0 && depth != 0 {
+                        assert!(plan.is_err());
+                        continue;
+                    }
+                    let plan = plan.unwrap();
+                    assert!(planner.verify(&plan, horizontal_position, depth));
+                    match fewest.get(&(horizontal_position, depth)) {
+                        Some(&length) => assert_eq!(plan.len(), length),
+                        None => assert!(plan.len() > 6),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_planned_courses_reach_the_target() {
+        let mut rng = Rng::new(2);
+
+        for _ in 0..200 {
+            let size = rng.range(1, 8) as usize;
+            let course = parse_course(&generate(&mut rng, size)).unwrap();
+            let max_value = match rng.chance(50) {
+                true => Some(rng.range(1, 20) as i64),
+                false => None,
+            };
+
+            for model in [Model::Simple, Model::Aim] {
+                let mut navigator = model.navigator();
+                navigator.follow(&course).unwrap();
+                let target = (navigator.horizontal_position(), navigator.depth());
+                // The generated values are below 10, so the generated course
+                // itself fits the limits unless the maximum value is lower.
+                let planner = Planner {
+                    model,
+                    max_commands: match max_value.is_some_and(|max_value| max_value < 9) {
+                        true => None,
+                        false => Some(course.len().max(3)),
+                    },
+                    max_value,
+                };
+
+                let plan = planner.plan(target.0, target.1).unwrap();
+                assert!(planner.verify(&plan, target.0, target.1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_beyond_i32() {
+        let input = "forward 100000\ndown 100000\nforward 100000\nforward 100000";
+
+        assert_eq!(part1(input), Ok(300_000 * 100_000));
+        assert_eq!(part2(input), Ok(300_000 * 20_000_000_000));
+        assert_eq!(
+            part1("forward 3000000000\nup 3000000000"),
+            Ok(-9_000_000_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            part1("forward 4294967296\ndown 4294967296"),
+            Err(CourseError::Overflow)
+        );
+        assert_eq!(
+            part2("down 3037000500\nforward 3037000500"),
+            Err(CourseError::Overflow)
+        );
+        assert_eq!(
+            part1("forward 9223372036854775807\nforward 1"),
+            Err(CourseError::Overflow)
+        );
+        assert_eq!(
+            Submarine::new().move_submarine(Command::Up(i64::MIN)).err(),
+            Some(CourseError::Overflow)
+        );
+        assert!(SubmarineWithDrag::new(50)
+            .follow(&[Command::Down(i64::MAX), Command::Forward(1)])
+            .is_err());
+
+        let mut submarine = SubmarineV2::new();
+        submarine.move_submarine(Command::Down(i64::MAX)).unwrap();
+        assert!(submarine.move_submarine(Command::Forward(2)).is_err());
+        assert_eq!(
+            (
+                submarine.horizontal_position,
+                submarine.depth,
+                submarine.aim
+            ),
+            (0, 0, i64::MAX)
+        );
+        assert_eq!(
+            CourseError::Overflow.to_string(),
+            "the submarine position, depth or aim overflows"
+        );
+        assert!(Planner::new(Model::Aim).plan(1, i64::MIN).is_err());
+        assert!(Planner::new(Model::Aim).plan(3, i64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_course_script() {
+        let script = "# Same course as the example\n\
+let step = 5\n\
+macro dive {\n\
+    down $step  # aim down\n\
+    forward 8\n\
+}\n\
+forward $step\n\
+dive\n\
+up 3\n\
+repeat 2 {\n\
+    repeat 4 {\n\
+        down 1\n\
+    }\n\
+}\n\
+forward 2\n";
+
+        assert_eq!(
+            parse_course(script).unwrap(),
+            parse_course("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2")
+                .unwrap()
+                .into_iter()
+                .flat_map(|command| match command {
+                    Command::Down(8) => vec![Command::Down(1); 8],
+                    command => vec![command],
+                })
+                .collect::<Vec<Command>>()
+        );
+        assert_eq!(part1(script), Ok(150));
+        assert_eq!(part2(script), Ok(900));
+        assert_eq!(
+            parse_course(
+                "let n = 0\nrepeat $n {\nforward 1\n}\nlet n = 2\nrepeat $n {\nforward 1\n}"
+            ),
+            Ok(vec![Command::Forward(1); 2])
+        );
+    }
+
+    #[test]
+    fn test_course_script_errors() {
+        let error = |script: &str| parse_course(script).unwrap_err().to_string();
+
+        assert_eq!(
+            error("forward 1\nrepeat 2 {\n  down 1\n"),
+            "line 2, column 10: missing '}'"
+        );
+        assert_eq!(error("forward 1\n}"), "line 2, column 1: unexpected '}'");
+        assert_eq!(
+            error("repeat 2 {\n  up x\n}"),
+            "line 2, column 6: unsupported submarine command value"
+        );
+        assert_eq!(
+            error("  forward $speed"),
+            "line 1, column 11: undefined variable"
+        );
+        assert_eq!(error("\n\ndive"), "line 3, column 1: undefined macro");
+        assert_eq!(
+            error("macro a {\n  b\n}\nmacro b {\n  a\n}\na"),
+            "line 5, column 3: recursive macro"
+        );
+        assert_eq!(
+            error("let n = -1\nrepeat $n {\n}"),
+            "line 2, column 1: the repeat count must not be negative"
+        );
+        assert_eq!(
+            error("repeat 1000 {\n  repeat 1000 {\n    forward 1\n  }\n}"),
+            "line 3, column 5: the script expands to too many commands"
+        );
+        assert_eq!(
+            error("repeat 9223372036854775807 {\n}"),
+            "line 1, column 1: the script expands to too many commands"
+        );
+        assert_eq!(
+            error(&"repeat 1 {\n".repeat(200)),
+            "line 101, column 10: blocks nested too deeply"
+        );
+        assert_eq!(
+            error("let 2 = 3"),
+            "line 1, column 1: expected 'let NAME = VALUE'"
+        );
+        assert_eq!(
+            error("macro up {\n}"),
+            "line 1, column 1: expected 'macro NAME {'"
+        );
+    }
+
+    #[test]
+    fn test_course_script_nesting() {
+        let mut script = String::from("macro m0 {\n  forward 1\n}\n");
+        for index in 1..60 {
+            script.push_str(&format!(
+                "macro m{} {{\n  repeat 1 {{\n    m{}\n  }}\n}}\n",
+                index,
+                index - 1
+            ));
+        }
+
+        assert_eq!(
+            parse_course(&format!("{}m40", script)),
+            Ok(vec![Command::Forward(1)])
+        );
+        assert!(parse_course(&format!("{}m59", script))
+            .unwrap_err()
+            .to_string()
+            .ends_with("blocks and macro calls nested too deeply"));
+    }
 }
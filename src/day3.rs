@@ -70,58 +70,200 @@ Finally, to find the life support rating, multiply the oxygen generator rating (
 Use the binary numbers in your diagnostic report to calculate the oxygen generator rating and CO2 scrubber rating, then multiply them together. What is the life support rating of the submarine? (Be sure to represent your answer in decimal, not binary.)
 */
 
-pub fn gamma_rate(numbers: Vec<i32>, binary_number_length: usize) -> i32 {
-    let mut counts_of_ones: Vec<i32> = vec![0; binary_number_length];
-    let mut counts_of_zeroes: Vec<i32> = vec![0; binary_number_length];
-    let mut gamma_rate: i32 = 0;
-
-    for number in numbers {
-        for position in 0..binary_number_length {
-            match number & (1 << (binary_number_length - 1 - position)) > 0 {
-                true => counts_of_ones[position] += 1,
-                false => counts_of_zeroes[position] += 1,
+use crate::rng::Rng;
+use crate::{AocError, Explain};
+use std::fmt;
+use std::str::FromStr;
+
+/// Binary number of any width, the most significant bit first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitString {
+    bits: Vec<bool>,
+}
+
+impl FromStr for BitString {
+    type Err = &'static str;
+
+    fn from_str(binary_number: &str) -> Result<Self, Self::Err> {
+        binary_number
+            .chars()
+            .map(|bit| match bit {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err("Invalid binary number"),
+            })
+            .collect::<Result<Vec<bool>, &'static str>>()
+            .map(BitString::new)
+    }
+}
+
+impl fmt::Display for BitString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &bit in &self.bits {
+            write!(f, "{}", if bit { '1' } else { '0' })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BitString {
+    pub fn new(bits: Vec<bool>) -> Self {
+        BitString { bits }
+    }
+
+    pub fn width(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Bit at `position`, counted from the most significant bit.
+    pub fn get(&self, position: usize) -> Option<bool> {
+        self.bits.get(position).copied()
+    }
+
+    pub fn complement(&self) -> BitString {
+        BitString::new(self.bits.iter().map(|&bit| !bit).collect())
+    }
+
+    /// 32-bit digits, the least significant first.
+    fn to_digits(&self) -> Vec<u32> {
+        let mut digits = vec![0; self.width().div_ceil(32)];
+
+        for (position, &bit) in self.bits.iter().rev().enumerate() {
+            digits[position / 32] |= u32::from(bit) << (position % 32);
+        }
+
+        digits
+    }
+
+    fn from_digits(digits: &[u32]) -> BitString {
+        let bits: Vec<bool> = (0..digits.len() * 32)
+            .rev()
+            .map(|position| digits[position / 32] >> (position % 32) & 1 == 1)
+            .skip_while(|&bit| !bit)
+            .collect();
+
+        match bits.is_empty() {
+            true => BitString::new(vec![false]),
+            false => BitString::new(bits),
+        }
+    }
+
+    /// Product of the two numbers, without leading zeros.
+    pub fn multiply(&self, other: &BitString) -> BitString {
+        let (left, right) = (self.to_digits(), other.to_digits());
+        let mut product = vec![0; left.len() + right.len()];
+
+        for (i, &left_digit) in left.iter().enumerate() {
+            let mut carry = 0;
+
+            for (j, &right_digit) in right.iter().enumerate() {
+                let digit = u64::from(product[i + j])
+                    + u64::from(left_digit) * u64::from(right_digit)
+                    + carry;
+                product[i + j] = digit as u32;
+                carry = digit >> 32;
             }
+            product[i + right.len()] = carry as u32;
         }
+
+        BitString::from_digits(&product)
     }
 
-    for position in 0..binary_number_length {
-        match counts_of_ones[position] > counts_of_zeroes[position] {
-            true => gamma_rate |= 1 << (binary_number_length - 1 - position),
-            false => continue,
+    /// Value in base 10.
+    pub fn to_decimal(&self) -> String {
+        const BILLION: u64 = 1_000_000_000;
+
+        let mut digits = self.to_digits();
+        let mut groups = Vec::new();
+
+        // Divide by 10^9 until nothing is left, the remainders are the groups of
+        // 9 decimal digits, the least significant first.
+        loop {
+            let mut remainder = 0;
+
+            for digit in digits.iter_mut().rev() {
+                let value = remainder << 32 | u64::from(*digit);
+                *digit = (value / BILLION) as u32;
+                remainder = value % BILLION;
+            }
+            groups.push(remainder);
+
+            if digits.iter().all(|&digit| digit == 0) {
+                break;
+            }
+        }
+
+        let mut decimal = groups.pop().unwrap_or(0).to_string();
+        for group in groups.iter().rev() {
+            decimal.push_str(&format!("{:09}", group));
         }
+
+        decimal
     }
+}
 
-    gamma_rate
+/// Most common bit of each position.
+pub fn gamma_rate(binary_numbers: &[BitString]) -> BitString {
+    let width = binary_numbers.first().map_or(0, BitString::width);
+
+    BitString::new(
+        (0..width)
+            .map(|position| {
+                let number_of_ones = binary_numbers
+                    .iter()
+                    .filter(|number| number.get(position) == Some(true))
+                    .count();
+
+                number_of_ones > binary_numbers.len() - number_of_ones
+            })
+            .collect(),
+    )
 }
 
-pub fn epsilon_rate(gamma_rate: i32, binary_number_length: usize) -> i32 {
-    !gamma_rate & ((1 << binary_number_length) - 1)
+pub fn epsilon_rate(gamma_rate: &BitString) -> BitString {
+    gamma_rate.complement()
 }
 
-pub fn part1(input: &str) -> i32 {
-    let binary_number_length = input.split('\n').into_iter().next().unwrap().len();
+/// Binary numbers of the report, all of the same width.
+pub fn parse_report(input: &str) -> Result<Vec<BitString>, &'static str> {
+    let binary_numbers: Vec<&str> = input.lines().filter(|line| !line.is_empty()).collect();
+    let binary_number_length = binary_numbers
+        .first()
+        .ok_or("Empty diagnostic report")?
+        .len();
+
+    if binary_numbers
+        .iter()
+        .any(|number| number.len() != binary_number_length)
+    {
+        return Err("Binary numbers of different widths");
+    }
 
-    let numbers: Vec<i32> = input
-        .split('\n')
-        .filter(|line| !line.is_empty())
-        .map(|number| i32::from_str_radix(number, 2).unwrap())
-        .collect();
+    binary_numbers.iter().map(|number| number.parse()).collect()
+}
 
-    let gamma_rate = gamma_rate(numbers, binary_number_length);
+/// Power consumption, in base 10.
+pub fn part1(input: &str) -> Result<String, &'static str> {
+    let binary_numbers = parse_report(input)?;
+    let gamma_rate = gamma_rate(&binary_numbers);
 
-    gamma_rate * epsilon_rate(gamma_rate, binary_number_length)
+    Ok(gamma_rate.multiply(&epsilon_rate(&gamma_rate)).to_decimal())
 }
 
-pub fn oxygen_generator_rating(binary_numbers: &[&str]) -> i32 {
+pub fn oxygen_generator_rating(binary_numbers: &[BitString]) -> Result<BitString, &'static str> {
     rating(binary_numbers, &oxygen_generator_condition)
 }
 
-pub fn co2_scrubber_rating(binary_numbers: &[&str]) -> i32 {
+pub fn co2_scrubber_rating(binary_numbers: &[BitString]) -> Result<BitString, &'static str> {
     rating(binary_numbers, &co2_scrubber_condition)
 }
 
-pub fn rating(binary_numbers: &[&str], condition_function: &dyn Fn(usize, usize) -> bool) -> i32 {
-    let binary_number_length = binary_numbers[0].len();
+pub fn rating(
+    binary_numbers: &[BitString],
+    condition_function: &dyn Fn(usize, usize) -> bool,
+) -> Result<BitString, &'static str> {
+    let binary_number_length = binary_numbers.first().ok_or("No binary number")?.width();
 
     let mut rating_list = binary_numbers.to_owned();
 
@@ -132,22 +274,23 @@ pub fn rating(binary_numbers: &[&str], condition_function: &dyn Fn(usize, usize)
 
         let number_of_ones = &rating_list
             .iter()
-            .filter(|rating| &rating[bit_position..bit_position + 1] == "1")
+            .filter(|rating| rating.get(bit_position) == Some(true))
             .count();
         let number_of_zeroes = &rating_list
             .iter()
-            .filter(|rating| &rating[bit_position..bit_position + 1] == "0")
+            .filter(|rating| rating.get(bit_position) == Some(false))
             .count();
 
-        let bit_to_keep = if condition_function(*number_of_ones, *number_of_zeroes) {
-            "1"
-        } else {
-            "0"
-        };
-        rating_list.retain(|rating| &rating[bit_position..bit_position + 1] == bit_to_keep);
+        // All the numbers share this bit, none of them can be discarded.
+        if *number_of_ones == 0 || *number_of_zeroes == 0 {
+            continue;
+        }
+
+        let bit_to_keep = condition_function(*number_of_ones, *number_of_zeroes);
+        rating_list.retain(|rating| rating.get(bit_position) == Some(bit_to_keep));
     }
 
-    i32::from_str_radix(rating_list[0], 2).unwrap()
+    Ok(rating_list.swap_remove(0))
 }
 
 pub fn oxygen_generator_condition(number_of_ones: usize, number_of_zeroes: usize) -> bool {
@@ -158,60 +301,278 @@ pub fn co2_scrubber_condition(number_of_ones: usize, number_of_zeroes: usize) ->
     number_of_ones < number_of_zeroes
 }
 
-pub fn part2(input: &str) -> i32 {
-    let rating_list: Vec<&str> = input.lines().collect();
+/// Life support rating, in base 10.
+pub fn part2(input: &str) -> Result<String, &'static str> {
+    let rating_list = parse_report(input)?;
 
-    oxygen_generator_rating(&rating_list) * co2_scrubber_rating(&rating_list)
+    Ok(oxygen_generator_rating(&rating_list)?
+        .multiply(&co2_scrubber_rating(&rating_list)?)
+        .to_decimal())
+}
+
+pub struct Explanation;
+
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        let binary_numbers = parse_report(input)?;
+
+        Ok(match part {
+            1 => {
+                let gamma_rate = gamma_rate(&binary_numbers);
+                let epsilon_rate = epsilon_rate(&gamma_rate);
+
+                vec![
+                    format!(
+                        "Gamma rate, from the most common bits: {} = {}",
+                        gamma_rate,
+                        gamma_rate.to_decimal()
+                    ),
+                    format!(
+                        "Epsilon rate, from the least common bits: {} = {}",
+                        epsilon_rate,
+                        epsilon_rate.to_decimal()
+                    ),
+                    format!(
+                        "Power consumption: {} * {} = {}",
+                        gamma_rate.to_decimal(),
+                        epsilon_rate.to_decimal(),
+                        part1(input)?
+                    ),
+                ]
+            }
+            _ => {
+                let oxygen_generator_rating = oxygen_generator_rating(&binary_numbers)?;
+                let co2_scrubber_rating = co2_scrubber_rating(&binary_numbers)?;
+
+                vec![
+                    format!(
+                        "Oxygen generator rating: {} = {}",
+                        oxygen_generator_rating,
+                        oxygen_generator_rating.to_decimal()
+                    ),
+                    format!(
+                        "CO2 scrubber rating: {} = {}",
+                        co2_scrubber_rating,
+                        co2_scrubber_rating.to_decimal()
+                    ),
+                    format!(
+                        "Life support rating: {} * {} = {}",
+                        oxygen_generator_rating.to_decimal(),
+                        co2_scrubber_rating.to_decimal(),
+                        part2(input)?
+                    ),
+                ]
+            }
+        })
+    }
+}
+
+/// Random diagnostic report of `size` 12-bit numbers.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{:012b}\n", rng.below(1 << 12)))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day3::{
-        co2_scrubber_rating, epsilon_rate, gamma_rate, oxygen_generator_rating, part1, part2,
+        co2_scrubber_rating, epsilon_rate, gamma_rate, oxygen_generator_rating, parse_report,
+        part1, part2, BitString, Explanation,
     };
+    use crate::rng::Rng;
+    use crate::Explain;
+
+    static TEST_INPUT: &str =
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+
+    fn bit_string(binary_number: &str) -> BitString {
+        binary_number.parse().unwrap()
+    }
+
+    /// Example report with each number widened by `transform`.
+    fn widened_report(transform: impl Fn(&str) -> String) -> String {
+        TEST_INPUT
+            .lines()
+            .map(|line| transform(line) + "\n")
+            .collect()
+    }
+
+    #[test]
+    fn test_bit_string() {
+        assert_eq!(bit_string("00101").to_string(), "00101");
+        assert_eq!(bit_string("00101").width(), 5);
+        assert_eq!(bit_string("00101").get(2), Some(true));
+        assert_eq!(bit_string("00101").get(5), None);
+        assert_eq!(bit_string("00101").complement(), bit_string("11010"));
+        assert_eq!("012".parse::<BitString>(), Err("Invalid binary number"));
+        assert_eq!(bit_string("").to_decimal(), "0");
+        assert_eq!(bit_string("000").to_decimal(), "0");
+        assert_eq!(
+            bit_string(&"1".repeat(100)).to_decimal(),
+            "1267650600228229401496703205375"
+        );
+    }
+
+    #[test]
+    fn test_multiply() {
+        let mut rng = Rng::new(3);
+
+        for _ in 0..100 {
+            let (left, right) = (rng.next_u64(), rng.next_u64() >> rng.below(64));
+
+            assert_eq!(
+                bit_string(&format!("{:b}", left))
+                    .multiply(&bit_string(&format!("{:b}", right)))
+                    .to_decimal(),
+                (u128::from(left) * u128::from(right)).to_string()
+            );
+        }
+
+        let max = bit_string(&"1".repeat(128));
+        assert_eq!(
+            max.multiply(&max).to_decimal(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert_eq!(
+            bit_string("0011").multiply(&bit_string("0")),
+            bit_string("0")
+        );
+    }
 
     #[test]
     fn test_gamma_rate() {
         assert_eq!(
-            gamma_rate(vec![4, 30, 22, 23, 21, 15, 7, 28, 16, 25, 2, 10], 5),
-            22
+            gamma_rate(&parse_report(TEST_INPUT).unwrap()),
+            bit_string("10110")
         );
+        assert_eq!(gamma_rate(&[]), BitString::default());
     }
 
     #[test]
     fn test_epsilon_rate() {
-        assert_eq!(epsilon_rate(22, 5), 9);
+        assert_eq!(epsilon_rate(&bit_string("10110")), bit_string("01001"));
+    }
+
+    #[test]
+    fn test_epsilon_rate_is_complement_of_gamma_rate() {
+        let mut rng = Rng::new(3);
+
+        for _ in 0..100 {
+            let binary_number_length = rng.range(1, 200) as usize;
+            let numbers: Vec<BitString> = (0..rng.range(1, 50))
+                .map(|_| {
+                    BitString::new((0..binary_number_length).map(|_| rng.chance(50)).collect())
+                })
+                .collect();
+
+            let gamma_rate = gamma_rate(&numbers);
+            let epsilon_rate = epsilon_rate(&gamma_rate);
+
+            assert_eq!(gamma_rate.width(), binary_number_length);
+            assert!((0..binary_number_length)
+                .all(|position| gamma_rate.get(position) != epsilon_rate.get(position)));
+        }
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1("00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010"), 198);
+        assert_eq!(part1(TEST_INPUT), Ok(String::from("198")));
     }
 
     #[test]
     fn test_oxygen_generator_rating() {
         assert_eq!(
-            oxygen_generator_rating(&[
-                "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
-                "11001", "00010", "01010"
-            ]),
-            23
+            oxygen_generator_rating(&parse_report(TEST_INPUT).unwrap()),
+            Ok(bit_string("10111"))
         );
     }
 
     #[test]
     fn test_co2_scrubber_rating() {
         assert_eq!(
-            co2_scrubber_rating(&[
-                "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
-                "11001", "00010", "01010"
-            ]),
-            10
+            co2_scrubber_rating(&parse_report(TEST_INPUT).unwrap()),
+            Ok(bit_string("01010"))
         );
+        assert_eq!(
+            co2_scrubber_rating(&[bit_string("000"), bit_string("001")]),
+            Ok(bit_string("000"))
+        );
+        assert_eq!(co2_scrubber_rating(&[]), Err("No binary number"));
+    }
+
+    #[test]
+    fn test_parse_report() {
+        assert_eq!(
+            parse_report("101\n010\n"),
+            Ok(vec![bit_string("101"), bit_string("010")])
+        );
+        assert_eq!(parse_report("\n"), Err("Empty diagnostic report"));
+        assert_eq!(
+            parse_report("101\n01\n"),
+            Err("Binary numbers of different widths")
+        );
+        assert_eq!(parse_report("101\n012\n"), Err("Invalid binary number"));
+        assert_eq!(parse_report(&"1".repeat(200)).unwrap()[0].width(), 200);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2("00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n"), 230);
+        assert_eq!(part2(TEST_INPUT), Ok(String::from("230")));
+    }
+
+    #[test]
+    fn test_64_bit_report() {
+        // The leading zeros are shared: only the epsilon rate changes, to
+        // 2^64 - 1 - 22.
+        let input = widened_report(|line| format!("{:0>64}", line));
+
+        assert_eq!(
+            part1(&input),
+            Ok((22 * (u128::from(u64::MAX) - 22)).to_string())
+        );
+        assert_eq!(part2(&input), Ok(String::from("230")));
+    }
+
+    #[test]
+    fn test_100_bit_report() {
+        // Every column repeats one of the example, so the rates and ratings
+        // repeat the example ones.
+        let input = widened_report(|line| line.repeat(20));
+        let binary_numbers = parse_report(&input).unwrap();
+
+        assert_eq!(gamma_rate(&binary_numbers), bit_string(&"10110".repeat(20)));
+        assert_eq!(
+            part1(&input),
+            Ok(String::from(
+                "331086090284370525033619661064566291011140285042664190343750"
+            ))
+        );
+        assert_eq!(
+            oxygen_generator_rating(&binary_numbers),
+            Ok(bit_string(&"10111".repeat(20)))
+        );
+        assert_eq!(
+            part2(&input),
+            Ok(String::from(
+                "384594953360632428069356171943688115821021543231377594843750"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_explanation() {
+        assert_eq!(
+            Explanation::explain(1, TEST_INPUT),
+            Ok(vec![
+                String::from("Gamma rate, from the most common bits: 10110 = 22"),
+                String::from("Epsilon rate, from the least common bits: 01001 = 9"),
+                String::from("Power consumption: 22 * 9 = 198"),
+            ])
+        );
+        assert_eq!(
+            Explanation::explain(2, TEST_INPUT).unwrap()[0],
+            "Oxygen generator rating: 10111 = 23"
+        );
     }
 }
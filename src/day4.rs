@@ -60,6 +60,14 @@ The score of the winning board can now be calculated. Start by finding the sum o
 To guarantee victory against the giant squid, figure out which board will win first. What will your final score be if you choose that board?
 */
 
+use crate::rng::Rng;
+use crate::trace::Level;
+use crate::trace_event;
+use crate::{AocError, Explain};
+
+/// Largest number accepted, so that the score of any board fits in an `i32`.
+const MAX_NUMBER: i32 = 999;
+
 #[derive(Debug)]
 pub struct Board {
     pub board: Vec<Vec<i32>>,
@@ -101,6 +109,22 @@ impl Board {
     }
 }
 
+/// A board winning the game: its index, the draw it wins on (counted from 1),
+/// the number drawn then and the sum of its unmarked numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Win {
+    pub board: usize,
+    pub draw: usize,
+    pub number: i32,
+    pub sum: i32,
+}
+
+impl Win {
+    pub fn score(&self) -> i32 {
+        self.sum * self.number
+    }
+}
+
 #[derive(Debug)]
 pub struct BingoSubsystem {
     pub numbers: Vec<i32>,
@@ -108,39 +132,60 @@ pub struct BingoSubsystem {
     pub last_number_drawn: i32,
 }
 
+fn numbers_out_of_range(numbers: &[i32]) -> bool {
+    numbers.iter().any(|&number| number > MAX_NUMBER)
+}
+
 impl BingoSubsystem {
-    pub fn new(input: &str) -> Self {
+    pub fn new(input: &str) -> Result<Self, &'static str> {
+        // Marked numbers are set to -1, so negative numbers are left out.
+        let parse_number = |number: &str| number.parse::<i32>().ok().filter(|&number| number >= 0);
         let mut lines = input.lines();
 
-        let numbers = lines
+        let numbers: Vec<i32> = lines
             .next()
-            .unwrap()
+            .ok_or("Missing numbers to draw")?
             .split(',')
-            .filter_map(|number| number.parse::<i32>().ok())
+            .filter_map(parse_number)
             .collect();
 
         // Skip empty line between the drawn numbers and the bingo boards.
-        lines.next().unwrap();
+        lines.next().ok_or("Missing bingo boards")?;
 
         let raw_boards = lines
             .map(|line| {
                 line.split_whitespace()
-                    .filter_map(|number| number.parse::<i32>().ok())
+                    .filter_map(parse_number)
                     .collect::<Vec<i32>>()
             })
             .collect::<Vec<Vec<i32>>>();
 
+        if numbers_out_of_range(&numbers) || raw_boards.iter().any(|row| numbers_out_of_range(row))
+        {
+            return Err("Bingo numbers must be between 0 and 999");
+        }
+
         let mut bingo_boards: Vec<Board> = Vec::new();
 
         for card in raw_boards.split(|vector| vector.is_empty()) {
+            if card.is_empty() {
+                continue;
+            }
+            if card.len() != 5 || card.iter().any(|row| row.len() != 5) {
+                return Err("Bingo boards must have 5 rows of 5 numbers");
+            }
             bingo_boards.push(Board::new(card.to_vec()));
         }
 
-        Self {
+        if bingo_boards.is_empty() {
+            return Err("Missing bingo boards");
+        }
+
+        Ok(Self {
             numbers,
             boards: bingo_boards,
             last_number_drawn: -1,
-        }
+        })
     }
 
     pub fn find_winner_board(&self) -> Option<&Board> {
@@ -170,46 +215,148 @@ impl BingoSubsystem {
 
         self.last_number_drawn = *self.numbers.first().unwrap();
         self.numbers = self.numbers.drain(1..).collect();
+        trace_event!(
+            Level::Trace,
+            "day4",
+            "Drew {}, {} numbers left",
+            self.last_number_drawn,
+            self.numbers.len()
+        );
 
         Ok(&self.numbers)
     }
-}
 
-pub fn part1(input: &str) -> i32 {
-    let mut bingo_subsystem = BingoSubsystem::new(input);
-    let mut board_sum = 0;
+    /// Draws numbers until every board has won or none are left, and returns
+    /// the boards in the order they win. Boards winning on the same draw are
+    /// ranked by their position.
+    pub fn play(&mut self) -> Vec<Win> {
+        let mut has_won = vec![false; self.boards.len()];
+        let mut wins = Vec::new();
+        let mut draw = 0;
+
+        while wins.len() < self.boards.len() && self.draw().is_ok() {
+            draw += 1;
 
-    while bingo_subsystem.draw().is_ok() {
-        let winner_board = bingo_subsystem.find_winner_board();
-        match winner_board {
-            Some(board) => {
-                board_sum = board.sum();
-                break;
+            for (index, board) in self.boards.iter().enumerate() {
+                if has_won[index] || !board.is_winner() {
+                    continue;
+                }
+                has_won[index] = true;
+
+                let win = Win {
+                    board: index,
+                    draw,
+                    number: self.last_number_drawn,
+                    sum: board.sum(),
+                };
+                trace_event!(
+                    Level::Debug,
+                    "day4",
+                    "Board {} wins on draw {} (number {}): unmarked sum {}",
+                    index + 1,
+                    draw,
+                    win.number,
+                    win.sum
+                );
+                wins.push(win);
             }
-            _ => continue,
-        };
+        }
+
+        wins
+    }
+}
+
+/// First board to win.
+fn first_win(wins: &[Win]) -> Result<Win, &'static str> {
+    wins.first().copied().ok_or("No board wins")
+}
+
+/// First of the boards winning last, once all `boards` have won.
+fn last_win(wins: &[Win], boards: usize) -> Result<Win, &'static str> {
+    match wins.last() {
+        Some(last) if wins.len() == boards => Ok(*wins
+            .iter()
+            .find(|win| win.draw == last.draw)
+            .unwrap_or(last)),
+        _ => Err("Not every board wins"),
     }
+}
 
-    board_sum * bingo_subsystem.last_number_drawn
+pub fn part1(input: &str) -> Result<i32, &'static str> {
+    let wins = BingoSubsystem::new(input)?.play();
+
+    Ok(first_win(&wins)?.score())
 }
 
-pub fn part2(input: &str) -> i32 {
-    let mut bingo_subsystem = BingoSubsystem::new(input);
+pub fn part2(input: &str) -> Result<i32, &'static str> {
+    let mut bingo_subsystem = BingoSubsystem::new(input)?;
+    let wins = bingo_subsystem.play();
 
-    while bingo_subsystem.draw().is_ok() {
-        if bingo_subsystem.boards.len() == 1 {
-            break;
-        }
+    Ok(last_win(&wins, bingo_subsystem.boards.len())?.score())
+}
+
+pub struct Explanation;
 
-        bingo_subsystem.boards.retain(|board| !board.is_winner());
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        let mut bingo_subsystem = BingoSubsystem::new(input)?;
+        let boards = bingo_subsystem.boards.len();
+        let wins = bingo_subsystem.play();
+        let win = match part {
+            1 => first_win(&wins)?,
+            _ => last_win(&wins, boards)?,
+        };
+
+        Ok(vec![
+            format!(
+                "Board {} of {} is the {} to win, on draw {} (number {})",
+                win.board + 1,
+                boards,
+                if part == 1 { "first" } else { "last" },
+                win.draw,
+                win.number
+            ),
+            format!("Sum of its unmarked numbers: {}", win.sum),
+            format!(
+                "Final score: {} * {} = {}",
+                win.sum,
+                win.number,
+                win.score()
+            ),
+        ])
     }
+}
 
-    bingo_subsystem.boards[0].sum() * bingo_subsystem.last_number_drawn
+/// Random bingo game of `size` boards, with every number from 0 to 99 drawn.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut numbers: Vec<u64> = (0..100).collect();
+    rng.shuffle(&mut numbers);
+
+    let mut game = numbers
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<String>>()
+        .join(",");
+    game.push('\n');
+
+    for _ in 0..size {
+        rng.shuffle(&mut numbers);
+        game.push('\n');
+
+        for row in numbers[..25].chunks(5) {
+            let row: Vec<String> = row.iter().map(|number| format!("{:>2}", number)).collect();
+            game.push_str(&format!("{}\n", row.join(" ")));
+        }
+    }
+
+    game
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day4::{part1, part2, BingoSubsystem};
+    use crate::day4::{generate, part1, part2, BingoSubsystem, Explanation};
+    use crate::rng::Rng;
+    use crate::Explain;
 
     static TEST_INPUT: &str =
         "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
@@ -235,7 +382,7 @@ mod tests {
 
     #[test]
     fn test_bingo_subsystem() {
-        let bingo_subsystem = BingoSubsystem::new(TEST_INPUT);
+        let bingo_subsystem = BingoSubsystem::new(TEST_INPUT).unwrap();
 
         assert_eq!(
             bingo_subsystem.numbers,
@@ -278,7 +425,7 @@ mod tests {
 
     #[test]
     fn test_bingo_subsystem_find_winner_board() {
-        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT);
+        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT).unwrap();
 
         for _ in 0..12 {
             bingo_subsystem.draw().ok();
@@ -298,7 +445,7 @@ mod tests {
 
     #[test]
     fn test_bingo_subsystem_is_row_winner() {
-        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT);
+        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT).unwrap();
         bingo_subsystem.boards[0].board[0] = vec![-1, -1, -1, -1, -1];
 
         assert!(bingo_subsystem.boards[0].is_winner());
@@ -306,7 +453,7 @@ mod tests {
 
     #[test]
     fn test_bingo_subsystem_is_colum_winner() {
-        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT);
+        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT).unwrap();
         bingo_subsystem.boards[0].board[0] = vec![14, 21, 17, 24, -1];
         bingo_subsystem.boards[0].board[1] = vec![10, 16, 15, 9, -1];
         bingo_subsystem.boards[0].board[2] = vec![18, 8, 23, 26, -1];
@@ -318,7 +465,7 @@ mod tests {
 
     #[test]
     fn test_bingo_subsystem_draw() {
-        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT);
+        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT).unwrap();
         bingo_subsystem.draw().ok();
 
         assert_eq!(bingo_subsystem.boards[0].board[2][4], -1);
@@ -328,7 +475,7 @@ mod tests {
 
     #[test]
     fn test_bingo_subsystem_draw_11_times() {
-        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT);
+        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT).unwrap();
 
         for _ in 0..11 {
             bingo_subsystem.draw().ok();
@@ -339,7 +486,7 @@ mod tests {
 
     #[test]
     fn test_bingo_subsystem_draw_12_times() {
-        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT);
+        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT).unwrap();
 
         for _ in 0..12 {
             bingo_subsystem.draw().ok();
@@ -350,7 +497,7 @@ mod tests {
 
     #[test]
     fn test_bingo_board_sum() {
-        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT);
+        let mut bingo_subsystem = BingoSubsystem::new(TEST_INPUT).unwrap();
 
         for _ in 0..12 {
             bingo_subsystem.draw().ok();
@@ -359,13 +506,109 @@ mod tests {
         assert_eq!(bingo_subsystem.find_winner_board().unwrap().sum(), 188);
     }
 
+    #[test]
+    fn test_bingo_subsystem_invalid_input() {
+        assert!(BingoSubsystem::new("").is_err());
+        assert!(BingoSubsystem::new("1,2,3\n").is_err());
+        assert_eq!(
+            BingoSubsystem::new("1,2,3\n\n1 2 3\n4 5 6\n").err(),
+            Some("Bingo boards must have 5 rows of 5 numbers")
+        );
+
+        let board = vec![["1000000000"; 5].join(" "); 5].join("\n");
+        assert_eq!(
+            part1(&format!("1000000000\n\n{}\n", board)),
+            Err("Bingo numbers must be between 0 and 999")
+        );
+        assert_eq!(
+            part2(&format!("1000\n\n{}\n", board.replace("1000000000", "7"))),
+            Err("Bingo numbers must be between 0 and 999")
+        );
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(part1(TEST_INPUT), 4512);
+        assert_eq!(part1(TEST_INPUT), Ok(4512));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(TEST_INPUT), 1924);
+        assert_eq!(part2(TEST_INPUT), Ok(1924));
+
+        // Both boards win last, on the same draw.
+        let board =
+            " 1  2  3  4  5\n 6  7  8  9 10\n11 12 13 14 15\n16 17 18 19 20\n21 22 23 24 25\n";
+        assert_eq!(
+            part2(&format!("1,2,3,4,5,6\n\n{}\n{}", board, board)),
+            Ok(1550)
+        );
+    }
+
+    #[test]
+    fn test_part2_last_board_wins_late() {
+        let mut rng = Rng::new(4);
+
+        for size in 1..=10 {
+            let game = generate(&mut rng, size);
+            let numbers: Vec<i32> = game
+                .lines()
+                .next()
+                .unwrap()
+                .split(',')
+                .map(|number| number.parse().unwrap())
+                .collect();
+            // Draw and score of each board when it wins, the first of the
+            // boards winning last being kept.
+            let (_, score) = BingoSubsystem::new(&game)
+                .unwrap()
+                .boards
+                .iter()
+                .map(|board| {
+                    let marked = |draws: usize, row: usize, column: usize| {
+                        numbers[..draws].contains(&board.board[row][column])
+                    };
+                    let draws = (1..=numbers.len())
+                        .find(|&draws| {
+                            (0..5).any(|i| {
+                                (0..5).all(|j| marked(draws, i, j))
+                                    || (0..5).all(|j| marked(draws, j, i))
+                            })
+                        })
+                        .unwrap();
+                    let unmarked: i32 = board
+                        .board
+                        .iter()
+                        .flatten()
+                        .filter(|number| !numbers[..draws].contains(number))
+                        .sum();
+                    (draws, unmarked * numbers[draws - 1])
+                })
+                .rev()
+                .max_by_key(|&(draws, _)| draws)
+                .unwrap();
+
+            assert_eq!(part2(&game), Ok(score));
+        }
+    }
+
+    #[test]
+    fn test_explanation() {
+        assert_eq!(
+            Explanation::explain(1, TEST_INPUT),
+            Ok(vec![
+                String::from("Board 3 of 3 is the first to win, on draw 12 (number 24)"),
+                String::from("Sum of its unmarked numbers: 188"),
+                String::from("Final score: 188 * 24 = 4512"),
+            ])
+        );
+        assert_eq!(
+            Explanation::explain(2, TEST_INPUT).unwrap()[0],
+            "Board 2 of 3 is the last to win, on draw 15 (number 13)"
+        );
+
+        for (part, answer) in [(1, part1(TEST_INPUT)), (2, part2(TEST_INPUT))] {
+            assert!(Explanation::explain(part, TEST_INPUT).unwrap()[2]
+                .ends_with(&format!(" = {}", answer.unwrap())));
+        }
     }
 }
@@ -73,6 +73,9 @@ use std::collections::HashMap;
 use std::fmt;
 use std::str::Split;
 
+use crate::rng::Rng;
+use crate::{AocError, Explain};
+
 #[derive(Debug, Copy, Clone, PartialEq, std::hash::Hash)]
 pub struct Point {
     pub x: i32,
@@ -93,25 +96,27 @@ impl fmt::Display for Point {
 
 impl Eq for Point {}
 
+/// Largest coordinate accepted, well above the puzzle's so the map still fits in memory.
+const MAX_COORDINATE: i32 = 9999;
+
+fn parse_point(coordinates: &str) -> Result<Point, &'static str> {
+    let parse_coordinate = |coordinate: &str| match coordinate.parse::<i32>() {
+        Ok(coordinate) if (0..=MAX_COORDINATE).contains(&coordinate) => Ok(coordinate),
+        Ok(_) => Err("Coordinates must be between 0 and 9999"),
+        Err(_) => Err("Invalid line of vents"),
+    };
+    let (x, y) = coordinates.split_once(',').ok_or("Invalid line of vents")?;
+
+    Ok(Point::new(parse_coordinate(x)?, parse_coordinate(y)?))
+}
+
 pub fn extract_coordinates_from_line(line: &str) -> Result<(Point, Point), &'static str> {
     match line {
         "" => Err("Empty line"),
         _ => {
-            let parts: Vec<&str> = line.split(' ').collect();
-            let coordinates1 = parts[0].split(',').collect::<Vec<&str>>();
-            let coordinates2 = parts[2].split(',').collect::<Vec<&str>>();
-
-            let point1 = Point::new(
-                coordinates1[0].parse::<i32>().unwrap(),
-                coordinates1[1].parse::<i32>().unwrap(),
-            );
-
-            let point2 = Point::new(
-                coordinates2[0].parse::<i32>().unwrap(),
-                coordinates2[1].parse::<i32>().unwrap(),
-            );
+            let (point1, point2) = line.split_once(" -> ").ok_or("Invalid line of vents")?;
 
-            Ok((point1, point2))
+            Ok((parse_point(point1)?, parse_point(point2)?))
         }
     }
 }
@@ -167,13 +172,13 @@ pub fn calculate_line_points(
 pub fn generate_map(
     line_coordinates: Split<char>,
     ignore_diagonal_lines: bool,
-) -> HashMap<Point, i32> {
+) -> Result<HashMap<Point, i32>, &'static str> {
     let mut points: HashMap<Point, i32> = HashMap::new();
 
     for line in line_coordinates {
         match extract_coordinates_from_line(line) {
             Err("Empty line") => continue,
-            Err(err) => panic!("Unknown error occured: {}", err),
+            Err(err) => return Err(err),
             Ok((point1, point2)) => {
                 for point in calculate_line_points(point1, point2, ignore_diagonal_lines) {
                     *points.entry(point).or_insert(0) += 1;
@@ -182,32 +187,109 @@ pub fn generate_map(
         }
     }
 
-    points
+    Ok(points)
 }
 
-pub fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> Result<usize, &'static str> {
     let line_coordinates = input.split('\n');
 
-    generate_map(line_coordinates, true)
+    Ok(generate_map(line_coordinates, true)?
         .iter()
         .filter(|(_key, value)| value > &&1)
-        .count()
+        .count())
 }
 
-pub fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> Result<usize, &'static str> {
     let line_coordinates = input.split('\n');
 
-    generate_map(line_coordinates, false)
+    Ok(generate_map(line_coordinates, false)?
         .iter()
         .filter(|(_key, value)| value > &&1)
-        .count()
+        .count())
+}
+
+pub struct Explanation;
+
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        let (mut horizontal, mut vertical, mut diagonal) = (0, 0, 0);
+
+        for line in input.split('\n') {
+            if let Ok((point1, point2)) = extract_coordinates_from_line(line) {
+                if point1.y == point2.y {
+                    horizontal += 1;
+                } else if point1.x == point2.x {
+                    vertical += 1;
+                } else {
+                    diagonal += 1;
+                }
+            }
+        }
+
+        let overlaps = generate_map(input.split('\n'), part == 1)?
+            .values()
+            .filter(|&&lines| lines > 1)
+            .count();
+
+        Ok(vec![
+            format!(
+                "{} lines of vents: {} horizontal, {} vertical and {} diagonal",
+                horizontal + vertical + diagonal,
+                horizontal,
+                vertical,
+                diagonal
+            ),
+            String::from(if part == 1 {
+                "Diagonal lines are ignored"
+            } else {
+                "Diagonal lines are included"
+            }),
+            format!("{} points are covered by at least two lines", overlaps),
+        ])
+    }
+}
+
+/// Random list of `size` horizontal, vertical or diagonal lines of vents, in a
+/// 1000 by 1000 area.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut lines = String::new();
+
+    for _ in 0..size {
+        let (x1, y1) = (rng.below(1000) as i64, rng.below(1000) as i64);
+        let length = rng.range(1, 300) as i64;
+        let (dx, dy) = *rng.choose(&[(1, 0), (0, 1), (1, 1), (1, -1)]);
+        let direction = if rng.chance(50) { 1 } else { -1 };
+
+        // Shorten the line so it stays in the area.
+        let length = (0..=length)
+            .rev()
+            .find(|length| {
+                let x2 = x1 + direction * dx * length;
+                let y2 = y1 + direction * dy * length;
+
+                (0..1000).contains(&x2) && (0..1000).contains(&y2)
+            })
+            .unwrap_or(0);
+
+        lines.push_str(&format!(
+            "{},{} -> {},{}\n",
+            x1,
+            y1,
+            x1 + direction * dx * length,
+            y1 + direction * dy * length
+        ));
+    }
+
+    lines
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day5::{
-        calculate_gradient_and_intercept, calculate_line_points, generate_map, part1, part2, Point,
+        calculate_gradient_and_intercept, calculate_line_points, extract_coordinates_from_line,
+        generate, generate_map, part1, part2, Point,
     };
+    use crate::rng::Rng;
 
     static TEST_INPUT: &str = "0,9 -> 5,9
 8,0 -> 0,8
@@ -220,6 +302,27 @@ mod tests {
 0,0 -> 8,8
 5,5 -> 8,2";
 
+    #[test]
+    fn test_extract_coordinates_from_line() {
+        assert_eq!(
+            extract_coordinates_from_line("0,9 -> 5,9"),
+            Ok((Point::new(0, 9), Point::new(5, 9)))
+        );
+        assert_eq!(extract_coordinates_from_line(""), Err("Empty line"));
+        assert_eq!(
+            extract_coordinates_from_line("0,9 ->"),
+            Err("Invalid line of vents")
+        );
+        assert_eq!(
+            extract_coordinates_from_line("0,9 -> 5"),
+            Err("Invalid line of vents")
+        );
+        assert_eq!(
+            extract_coordinates_from_line("0,9 -> 5,99999"),
+            Err("Coordinates must be between 0 and 9999")
+        );
+    }
+
     #[test]
     fn test_calculate_gradient_and_intercept() {
         assert_eq!(
@@ -377,7 +480,7 @@ mod tests {
 
     #[test]
     fn test_generate_map_without_diagonal_lines() {
-        let map = generate_map(TEST_INPUT.split('\n'), true);
+        let map = generate_map(TEST_INPUT.split('\n'), true).unwrap();
 
         assert_eq!(map.len(), 21);
 
@@ -406,7 +509,7 @@ mod tests {
 
     #[test]
     fn test_generate_map_with_diagonal_lines() {
-        let map = generate_map(TEST_INPUT.split('\n'), false);
+        let map = generate_map(TEST_INPUT.split('\n'), false).unwrap();
 
         assert_eq!(map.len(), 39);
 
@@ -453,11 +556,22 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(TEST_INPUT), 5);
+        assert_eq!(part1(TEST_INPUT), Ok(5));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(TEST_INPUT), 12);
+        assert_eq!(part2(TEST_INPUT), Ok(12));
+    }
+
+    #[test]
+    fn test_part2_covers_part1() {
+        let mut rng = Rng::new(5);
+
+        for _ in 0..10 {
+            let input = generate(&mut rng, 100);
+
+            assert!(part2(&input) >= part1(&input));
+        }
     }
 }
@@ -64,6 +64,9 @@ How many lanternfish would there be after 256 days?
 
 use std::collections::VecDeque;
 
+use crate::rng::Rng;
+use crate::{AocError, Explain};
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Lanternfish {
     internal_timer: usize,
@@ -156,36 +159,89 @@ impl FishPoolv2 {
     }
 }
 
-pub fn parse_input(input: &str) -> Vec<usize> {
+pub fn parse_input(input: &str) -> Result<Vec<usize>, &'static str> {
     let mut parsed_input: Vec<usize> = Vec::new();
 
     for number_string in input.trim().split(',') {
         match number_string.parse::<usize>() {
+            Ok(number) if number > 8 => return Err("Timers must be between 0 and 8"),
             Ok(number) => parsed_input.push(number),
             Err(_error) => continue,
         }
     }
 
-    parsed_input
+    Ok(parsed_input)
 }
 
-pub fn part1(input: &str) -> usize {
-    let mut fish_pool = FishPool::new(parse_input(input));
+pub fn part1(input: &str) -> Result<usize, &'static str> {
+    let mut fish_pool = FishPool::new(parse_input(input)?);
     fish_pool.simulate(80);
 
-    fish_pool.pool.len()
+    Ok(fish_pool.pool.len())
 }
 
-pub fn part2(input: &str) -> usize {
-    let mut fish_pool = FishPoolv2::new(parse_input(input));
+pub fn part2(input: &str) -> Result<usize, &'static str> {
+    let mut fish_pool = FishPoolv2::new(parse_input(input)?);
     fish_pool.simulate(256);
 
-    fish_pool.pool.into_iter().sum()
+    Ok(fish_pool.pool.into_iter().sum())
+}
+
+pub struct Explanation;
+
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        let fishes = parse_input(input)?;
+        let initial_fishes = fishes.len();
+
+        Ok(match part {
+            1 => {
+                let mut fish_pool = FishPool::new(fishes);
+                fish_pool.simulate(80);
+
+                vec![format!(
+                    "{} lanternfish at first, {} after 80 days",
+                    initial_fishes,
+                    fish_pool.pool.len()
+                )]
+            }
+            _ => {
+                let mut fish_pool = FishPoolv2::new(fishes);
+                fish_pool.simulate(256);
+
+                vec![
+                    format!(
+                        "{} lanternfish at first, {} after 256 days",
+                        initial_fishes,
+                        fish_pool.pool.iter().sum::<usize>()
+                    ),
+                    format!(
+                        "Lanternfish per timer value: {}",
+                        fish_pool
+                            .pool
+                            .iter()
+                            .enumerate()
+                            .map(|(timer, fishes)| format!("{}: {}", timer, fishes))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                ]
+            }
+        })
+    }
+}
+
+/// Random school of `size` lanternfish.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let timers: Vec<String> = (0..size).map(|_| rng.range(1, 5).to_string()).collect();
+
+    format!("{}\n", timers.join(","))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day6::{parse_input, part1, part2, FishPool, FishPoolv2, Lanternfish};
+    use crate::rng::Rng;
     use std::collections::VecDeque;
 
     static TEST_INPUT: &str = "3,4,3,1,2
@@ -221,7 +277,7 @@ mod tests {
 
     #[test]
     fn test_fishpool_simulate_1_day() {
-        let mut fish_pool = FishPool::new(parse_input(TEST_INPUT));
+        let mut fish_pool = FishPool::new(parse_input(TEST_INPUT).unwrap());
         assert_eq!(
             fish_pool.pool,
             vec![
@@ -247,7 +303,7 @@ mod tests {
 
     #[test]
     fn test_fishpool_simulate_2_days() {
-        let mut fish_pool = FishPool::new(parse_input(TEST_INPUT));
+        let mut fish_pool = FishPool::new(parse_input(TEST_INPUT).unwrap());
         fish_pool.simulate(2);
         assert_eq!(
             fish_pool.pool,
@@ -264,7 +320,7 @@ mod tests {
 
     #[test]
     fn test_fishpool_simulate_4_days() {
-        let mut fish_pool = FishPool::new(parse_input(TEST_INPUT));
+        let mut fish_pool = FishPool::new(parse_input(TEST_INPUT).unwrap());
         fish_pool.simulate(4);
         assert_eq!(
             fish_pool.pool,
@@ -284,7 +340,7 @@ mod tests {
 
     #[test]
     fn test_fishpool_simulate_18_days() {
-        let mut fish_pool = FishPool::new(parse_input(TEST_INPUT));
+        let mut fish_pool = FishPool::new(parse_input(TEST_INPUT).unwrap());
         fish_pool.simulate(18);
         assert_eq!(
             fish_pool.pool,
@@ -321,7 +377,7 @@ mod tests {
 
     #[test]
     fn test_fishpoolv2() {
-        let fish_pool = FishPoolv2::new(parse_input(TEST_INPUT));
+        let fish_pool = FishPoolv2::new(parse_input(TEST_INPUT).unwrap());
         assert_eq!(
             fish_pool.pool,
             VecDeque::from(vec![0, 1, 1, 2, 1, 0, 0, 0, 0])
@@ -330,7 +386,7 @@ mod tests {
 
     #[test]
     fn test_fishpoolv2_simulate_1_day() {
-        let mut fish_pool = FishPoolv2::new(parse_input(TEST_INPUT));
+        let mut fish_pool = FishPoolv2::new(parse_input(TEST_INPUT).unwrap());
         fish_pool.simulate(1);
         assert_eq!(
             fish_pool.pool,
@@ -340,7 +396,7 @@ mod tests {
 
     #[test]
     fn test_fishpoolv2_simulate_2_day() {
-        let mut fish_pool = FishPoolv2::new(parse_input(TEST_INPUT));
+        let mut fish_pool = FishPoolv2::new(parse_input(TEST_INPUT).unwrap());
         fish_pool.simulate(2);
         assert_eq!(
             fish_pool.pool,
@@ -350,7 +406,7 @@ mod tests {
 
     #[test]
     fn test_fishpoolv2_simulate_4_days() {
-        let mut fish_pool = FishPoolv2::new(parse_input(TEST_INPUT));
+        let mut fish_pool = FishPoolv2::new(parse_input(TEST_INPUT).unwrap());
         fish_pool.simulate(4);
         assert_eq!(
             fish_pool.pool,
@@ -360,7 +416,7 @@ mod tests {
 
     #[test]
     fn test_fishpoolv2_simulate_18_days() {
-        let mut fish_pool = FishPoolv2::new(parse_input(TEST_INPUT));
+        let mut fish_pool = FishPoolv2::new(parse_input(TEST_INPUT).unwrap());
         fish_pool.simulate(18);
         assert_eq!(
             fish_pool.pool,
@@ -368,13 +424,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fishpool_versions_agree() {
+        let mut rng = Rng::new(6);
+
+        for _ in 0..100 {
+            let fishes: Vec<usize> = (0..rng.below(20)).map(|_| rng.below(9) as usize).collect();
+            let number_of_days = rng.below(60) as u32;
+
+            let mut fish_pool = FishPool::new(fishes.clone());
+            let mut fish_pool_v2 = FishPoolv2::new(fishes);
+            fish_pool.simulate(number_of_days);
+            fish_pool_v2.simulate(number_of_days);
+
+            for timer in 0..9 {
+                let fishes_with_timer = fish_pool
+                    .pool
+                    .iter()
+                    .filter(|fish| fish.internal_timer == timer)
+                    .count();
+                assert_eq!(fishes_with_timer, fish_pool_v2.pool[timer]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(parse_input(TEST_INPUT), Ok(vec![3, 4, 3, 1, 2]));
+        assert_eq!(parse_input("3,9"), Err("Timers must be between 0 and 8"));
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(part1(TEST_INPUT), 5934);
+        assert_eq!(part1(TEST_INPUT), Ok(5934));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(TEST_INPUT), 26984457539);
+        assert_eq!(part2(TEST_INPUT), Ok(26984457539));
     }
 }
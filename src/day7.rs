@@ -58,6 +58,9 @@ This costs a total of 168 fuel. This is the new cheapest possible outcome; the o
 Determine the horizontal position that the crabs can align to using the least fuel possible so they can make you an escape route! How much fuel must they spend to align to that position?
 */
 
+use crate::rng::Rng;
+use crate::{AocError, Explain};
+
 pub struct CrabSubmarine {
     horizontal_position: u32,
     aligned_position: u32,
@@ -82,96 +85,121 @@ impl CrabSubmarine {
     }
 }
 
-pub fn parse_input(input: &str) -> Vec<u32> {
+/// Largest position accepted, every position in between is tried.
+const MAX_POSITION: u32 = 9999;
+
+pub fn parse_input(input: &str) -> Result<Vec<u32>, &'static str> {
     let mut parsed_input: Vec<u32> = Vec::new();
 
     for number_string in input.trim().split(',') {
         match number_string.parse::<u32>() {
+            Ok(number) if number > MAX_POSITION => {
+                return Err("Positions must be between 0 and 9999")
+            }
             Ok(number) => parsed_input.push(number),
             Err(_error) => continue,
         }
     }
 
     parsed_input.sort();
-    parsed_input
+    Ok(parsed_input)
 }
 
-pub fn part1(input: &str) -> i32 {
-    let horizontal_positions = parse_input(input);
-    let mut position_min: u32 = 0;
-    let mut position_max: u32 = 0;
-    let mut fuel_cost_totals: Vec<u32> = Vec::new();
-
-    if let Some(position) = horizontal_positions.first() {
-        position_min = *position
-    }
-    if let Some(position) = horizontal_positions.last() {
-        position_max = *position
-    }
+/// Fuel a crab submarine burns to move `distance` steps, in part 1.
+fn constant_fuel_cost(distance: u32) -> u64 {
+    u64::from(distance)
+}
 
-    for aligned_position in position_min..position_max {
-        let mut fuel_costs: Vec<u32> = Vec::new();
+/// Fuel a crab submarine burns to move `distance` steps, in part 2: each step
+/// costs one more than the previous one.
+fn increasing_fuel_cost(distance: u32) -> u64 {
+    u64::from(distance) * (u64::from(distance) + 1) / 2
+}
 
-        for position in &horizontal_positions {
-            fuel_costs.push(position.max(&aligned_position) - position.min(&aligned_position));
-        }
+/// Position between the leftmost and rightmost crabs that costs the least fuel,
+/// with the fuel it costs. The fuel is summed in 64 bits: even a million crabs
+/// at both ends of the range stay far below its limit.
+fn cheapest_alignment(
+    horizontal_positions: &[u32],
+    fuel_cost: fn(u32) -> u64,
+) -> Result<(u32, u64), &'static str> {
+    let position_min = *horizontal_positions
+        .first()
+        .ok_or("No crab submarine to align")?;
+    let position_max = *horizontal_positions
+        .last()
+        .ok_or("No crab submarine to align")?;
+
+    Ok((position_min..=position_max)
+        .map(|aligned_position| {
+            let fuel: u64 = horizontal_positions
+                .iter()
+                .map(|position| fuel_cost(position.abs_diff(aligned_position)))
+                .sum();
+
+            (aligned_position, fuel)
+        })
+        .min_by_key(|&(aligned_position, fuel)| (fuel, aligned_position))
+        .unwrap_or_default())
+}
 
-        fuel_cost_totals.push(fuel_costs.iter().sum());
-    }
+pub fn part1(input: &str) -> Result<u64, &'static str> {
+    let (_, fuel) = cheapest_alignment(&parse_input(input)?, constant_fuel_cost)?;
 
-    match fuel_cost_totals.iter().min() {
-        Some(minimal_cost) => *minimal_cost as i32,
-        None => -1,
-    }
+    Ok(fuel)
 }
 
-pub fn part2(input: &str) -> i32 {
-    let horizontal_positions = parse_input(input);
-    let mut position_min: u32 = 0;
-    let mut position_max: u32 = 0;
-    let mut fuel_cost_totals: Vec<u32> = Vec::new();
-    let mut fuel_costs_index: Vec<u32> = vec![0];
+pub fn part2(input: &str) -> Result<u64, &'static str> {
+    let (_, fuel) = cheapest_alignment(&parse_input(input)?, increasing_fuel_cost)?;
 
-    if let Some(position) = horizontal_positions.first() {
-        position_min = *position
-    }
-    if let Some(position) = horizontal_positions.last() {
-        position_max = *position
-    }
+    Ok(fuel)
+}
 
-    // Pre-calculate fuel costs for all positions.
-    for position in 1..=position_max {
-        fuel_costs_index.push(
-            if let Some(fuel_cost) = fuel_costs_index.last() {
-                fuel_cost
-            } else {
-                &0
-            } + position,
-        );
+pub struct Explanation;
+
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        let horizontal_positions = parse_input(input)?;
+        let (aligned_position, fuel) = cheapest_alignment(
+            &horizontal_positions,
+            match part {
+                1 => constant_fuel_cost,
+                _ => increasing_fuel_cost,
+            },
+        )?;
+
+        Ok(vec![
+            format!(
+                "{} crab submarines between positions {} and {}",
+                horizontal_positions.len(),
+                horizontal_positions[0],
+                horizontal_positions[horizontal_positions.len() - 1]
+            ),
+            format!(
+                "Cheapest alignment position: {}, costing {} fuel{}",
+                aligned_position,
+                fuel,
+                if part == 1 {
+                    ""
+                } else {
+                    " (each step costs one more than the previous one)"
+                }
+            ),
+        ])
     }
+}
 
-    for aligned_position in position_min..position_max {
-        let mut fuel_costs: Vec<u32> = Vec::new();
-
-        for position in &horizontal_positions {
-            fuel_costs.push(
-                fuel_costs_index
-                    [(position.max(&aligned_position) - position.min(&aligned_position)) as usize],
-            );
-        }
+/// Random positions of `size` crab submarines, between 0 and 1999.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let positions: Vec<String> = (0..size).map(|_| rng.below(2000).to_string()).collect();
 
-        fuel_cost_totals.push(fuel_costs.iter().sum());
-    }
-
-    match fuel_cost_totals.iter().min() {
-        Some(minimal_cost) => *minimal_cost as i32,
-        None => -1,
-    }
+    format!("{}\n", positions.join(","))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day7::{parse_input, part1, part2, CrabSubmarine};
+    use crate::day7::{parse_input, part1, part2, CrabSubmarine, Explanation};
+    use crate::Explain;
 
     static TEST_INPUT: &str = "16,1,2,0,4,2,7,1,2,14
 ";
@@ -199,17 +227,53 @@ mod tests {
     fn test_parse_input() {
         assert_eq!(
             parse_input(TEST_INPUT),
-            vec![0, 1, 1, 2, 2, 2, 4, 7, 14, 16]
+            Ok(vec![0, 1, 1, 2, 2, 2, 4, 7, 14, 16])
+        );
+        assert_eq!(
+            parse_input("1,10000"),
+            Err("Positions must be between 0 and 9999")
         );
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(TEST_INPUT), 37);
+        assert_eq!(part1(TEST_INPUT), Ok(37));
+        assert_eq!(part1("3"), Ok(0));
+        assert_eq!(part1(""), Err("No crab submarine to align"));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(TEST_INPUT), 168);
+        assert_eq!(part2(TEST_INPUT), Ok(168));
+    }
+
+    #[test]
+    fn test_extreme_positions() {
+        let input = format!(
+            "{},{}",
+            vec!["0"; 500].join(","),
+            vec!["9999"; 500].join(",")
+        );
+
+        assert_eq!(part1(&input), Ok(500 * 9999));
+        assert_eq!(part2(&input), Ok(500 * (12_497_500 + 12_502_500)));
+    }
+
+    #[test]
+    fn test_explanation() {
+        assert_eq!(
+            Explanation::explain(1, TEST_INPUT).unwrap()[1],
+            "Cheapest alignment position: 2, costing 37 fuel"
+        );
+        assert_eq!(
+            Explanation::explain(2, TEST_INPUT).unwrap()[1],
+            "Cheapest alignment position: 5, costing 168 fuel (each step costs one more than the previous one)"
+        );
+        assert!(Explanation::explain(1, "").is_err());
+
+        for (part, answer) in [(1, part1(TEST_INPUT)), (2, part2(TEST_INPUT))] {
+            assert!(Explanation::explain(part, TEST_INPUT).unwrap()[1]
+                .contains(&format!("costing {} fuel", answer.unwrap())));
+        }
     }
 }
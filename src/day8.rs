@@ -131,6 +131,11 @@ For each entry, determine all of the wire/segment connections and decode the fou
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use crate::rng::Rng;
+use crate::trace::Level;
+use crate::trace_event;
+use crate::{AocError, Explain};
+
 pub fn sort_string_chars(strings: Vec<&str>) -> Vec<String> {
     strings
         .into_iter()
@@ -142,14 +147,17 @@ pub fn sort_string_chars(strings: Vec<&str>) -> Vec<String> {
         .collect()
 }
 
+/// Signal patterns and output value of one entry.
+fn split_entry(line: &str) -> Result<(&str, &str), &'static str> {
+    line.split_once(" | ")
+        .ok_or("Missing ' | ' between the signal patterns and the output value")
+}
+
 pub fn part1(input: &str) -> Result<u64, &'static str> {
     let mut segment_counter: u64 = 0;
 
     for line in input.trim().split('\n') {
-        for segment in line.split(" | ").collect::<Vec<&str>>()[1]
-            .split(' ')
-            .collect::<Vec<&str>>()
-        {
+        for segment in split_entry(line)?.1.split(' ').collect::<Vec<&str>>() {
             match segment.len() {
                 2 | 3 | 4 | 7 => segment_counter += 1,
                 _ => {}
@@ -159,7 +167,7 @@ pub fn part1(input: &str) -> Result<u64, &'static str> {
     Ok(segment_counter)
 }
 
-pub fn is_9(signal_pattern: &String, signal_patterns_map: &HashMap<&String, u32>) -> bool {
+pub fn is_9(signal_pattern: &str, signal_patterns_map: &HashMap<&String, u32>) -> bool {
     let signal_pattern_4 = signal_patterns_map
         .iter()
         .find_map(|(key, &value)| match value {
@@ -173,7 +181,7 @@ pub fn is_9(signal_pattern: &String, signal_patterns_map: &HashMap<&String, u32>
             .is_superset(&HashSet::<char>::from_iter(signal_pattern_4.chars())))
 }
 
-pub fn is_0(signal_pattern: &String, signal_patterns_map: &HashMap<&String, u32>) -> bool {
+pub fn is_0(signal_pattern: &str, signal_patterns_map: &HashMap<&String, u32>) -> bool {
     let signal_pattern_1 = signal_patterns_map
         .iter()
         .find_map(|(key, &value)| match value {
@@ -188,13 +196,13 @@ pub fn is_0(signal_pattern: &String, signal_patterns_map: &HashMap<&String, u32>
             .is_superset(&HashSet::<char>::from_iter(signal_pattern_1.chars())))
 }
 
-pub fn is_6(signal_pattern: &String, signal_patterns_map: &HashMap<&String, u32>) -> bool {
+pub fn is_6(signal_pattern: &str, signal_patterns_map: &HashMap<&String, u32>) -> bool {
     (signal_pattern.len() == 6)
         && !is_9(signal_pattern, signal_patterns_map)
         && !is_0(signal_pattern, signal_patterns_map)
 }
 
-pub fn is_3(signal_pattern: &String, signal_patterns_map: &HashMap<&String, u32>) -> bool {
+pub fn is_3(signal_pattern: &str, signal_patterns_map: &HashMap<&String, u32>) -> bool {
     let signal_pattern_1 = signal_patterns_map
         .iter()
         .find_map(|(key, &value)| match value {
@@ -208,7 +216,7 @@ pub fn is_3(signal_pattern: &String, signal_patterns_map: &HashMap<&String, u32>
             .is_superset(&HashSet::<char>::from_iter(signal_pattern_1.chars())))
 }
 
-pub fn is_5(signal_pattern: &String, signal_patterns_map: &HashMap<&String, u32>) -> bool {
+pub fn is_5(signal_pattern: &str, signal_patterns_map: &HashMap<&String, u32>) -> bool {
     let signal_pattern_9 = signal_patterns_map
         .iter()
         .find_map(|(key, &value)| match value {
@@ -223,12 +231,27 @@ pub fn is_5(signal_pattern: &String, signal_patterns_map: &HashMap<&String, u32>
             .is_subset(&HashSet::<char>::from_iter(signal_pattern_9.chars())))
 }
 
-pub fn is_2(signal_pattern: &String, signal_patterns_map: &HashMap<&String, u32>) -> bool {
+pub fn is_2(signal_pattern: &str, signal_patterns_map: &HashMap<&String, u32>) -> bool {
     (signal_pattern.len() == 5)
         && !is_3(signal_pattern, signal_patterns_map)
         && !is_5(signal_pattern, signal_patterns_map)
 }
 
+/// Signal pattern of each digit, e.g. `0=abcefg 1=cf ...`.
+fn describe_wiring(signal_patterns_map: &HashMap<&String, u32>) -> String {
+    let mut wiring = signal_patterns_map
+        .iter()
+        .map(|(pattern, digit)| (digit, pattern))
+        .collect::<Vec<_>>();
+    wiring.sort();
+
+    wiring
+        .iter()
+        .map(|(digit, pattern)| format!("{}={}", digit, pattern))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
 /*
 My approach seems way too complicated with the HashSet and HashMap,
 although it is a good learning exercice!
@@ -237,7 +260,8 @@ I like this approach, very straightforward:
 https://arturh85.github.io/adventofcode-rust-2021/src/adventofcode_rust_2021/day8.rs.html#1-347
 */
 
-pub fn part2(input: &str) -> Result<u64, &'static str> {
+/// Output value of one entry, once the wiring is deduced from its signal patterns.
+fn decode_output_value((signal_patterns, output_value): (&str, &str)) -> Option<u64> {
     /*
     2 segments: 1
     3 segments: 7
@@ -248,68 +272,162 @@ pub fn part2(input: &str) -> Result<u64, &'static str> {
     5 segments: 2, 3, 5 (3 is superset of 1, 5 is subset of 9, by elimination, that leaves 2)
     */
 
-    let mut sum_of_digits: u64 = 0;
+    let mut signal_patterns: Vec<String> =
+        sort_string_chars(signal_patterns.split(' ').collect::<Vec<&str>>());
+    signal_patterns.sort_by_key(|b| std::cmp::Reverse(b.len()));
 
-    for line in input.trim().split('\n') {
-        let splitted_line = line.split(" | ").collect::<Vec<&str>>();
+    let digit_output_values: Vec<String> =
+        sort_string_chars(output_value.split(' ').collect::<Vec<&str>>());
 
-        let mut signal_patterns: Vec<String> =
-            sort_string_chars(splitted_line[0].split(' ').collect::<Vec<&str>>());
-        signal_patterns.sort_by_key(|b| std::cmp::Reverse(b.len()));
+    let mut signal_patterns_map: HashMap<_, _> = signal_patterns
+        .iter()
+        .filter_map(|pattern| match pattern.len() {
+            2 => Some((pattern, 1)),
+            3 => Some((pattern, 7)),
+            4 => Some((pattern, 4)),
+            7 => Some((pattern, 8)),
+            _ => None,
+        })
+        .collect::<HashMap<_, _>>();
 
-        let digit_output_values: Vec<String> =
-            sort_string_chars(splitted_line[1].split(' ').collect::<Vec<&str>>());
+    for signal_pattern in &signal_patterns {
+        if is_9(signal_pattern, &signal_patterns_map) {
+            signal_patterns_map.insert(signal_pattern, 9);
+        }
+        if is_0(signal_pattern, &signal_patterns_map) {
+            signal_patterns_map.insert(signal_pattern, 0);
+        }
+        if is_6(signal_pattern, &signal_patterns_map) {
+            signal_patterns_map.insert(signal_pattern, 6);
+        }
+        if is_3(signal_pattern, &signal_patterns_map) {
+            signal_patterns_map.insert(signal_pattern, 3);
+        }
+        if is_5(signal_pattern, &signal_patterns_map) {
+            signal_patterns_map.insert(signal_pattern, 5);
+        }
+        if is_2(signal_pattern, &signal_patterns_map) {
+            signal_patterns_map.insert(signal_pattern, 2);
+        }
+    }
 
-        let mut signal_patterns_map: HashMap<_, _> = signal_patterns
-            .iter()
-            .filter_map(|pattern| match pattern.len() {
-                2 => Some((pattern, 1)),
-                3 => Some((pattern, 7)),
-                4 => Some((pattern, 4)),
-                7 => Some((pattern, 8)),
-                _ => None,
-            })
-            .collect::<HashMap<_, _>>();
+    let number = digit_output_values
+        .iter()
+        .map(|digit| match signal_patterns_map.get(&digit) {
+            Some(value) => value.to_string(),
+            None => String::from(""),
+        })
+        .collect::<Vec<String>>()
+        .join("")
+        .parse::<u64>()
+        .ok()?;
+
+    trace_event!(
+        Level::Debug,
+        "day8",
+        "Decoded wiring {}: output {}",
+        describe_wiring(&signal_patterns_map),
+        number
+    );
+
+    Some(number)
+}
 
-        for signal_pattern in &signal_patterns {
-            if is_9(signal_pattern, &signal_patterns_map) {
-                signal_patterns_map.insert(signal_pattern, 9);
-            }
-            if is_0(signal_pattern, &signal_patterns_map) {
-                signal_patterns_map.insert(signal_pattern, 0);
-            }
-            if is_6(signal_pattern, &signal_patterns_map) {
-                signal_patterns_map.insert(signal_pattern, 6);
-            }
-            if is_3(signal_pattern, &signal_patterns_map) {
-                signal_patterns_map.insert(signal_pattern, 3);
-            }
-            if is_5(signal_pattern, &signal_patterns_map) {
-                signal_patterns_map.insert(signal_pattern, 5);
-            }
-            if is_2(signal_pattern, &signal_patterns_map) {
-                signal_patterns_map.insert(signal_pattern, 2);
+pub fn part2(input: &str) -> Result<u64, &'static str> {
+    let mut sum = 0;
+
+    for line in input.trim().split('\n') {
+        sum += decode_output_value(split_entry(line)?).unwrap_or(0);
+    }
+
+    Ok(sum)
+}
+
+pub struct Explanation;
+
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        Ok(match part {
+            1 => vec![format!(
+                "{} digits of the output values use a unique number of segments (1, 4, 7 or 8)",
+                part1(input)?
+            )],
+            _ => {
+                let output_values: Vec<u64> = input
+                    .trim()
+                    .split('\n')
+                    .map(split_entry)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter()
+                    .filter_map(decode_output_value)
+                    .collect();
+
+                vec![
+                    format!(
+                        "Decoded output values of the {} displays: {}",
+                        output_values.len(),
+                        output_values
+                            .iter()
+                            .map(u64::to_string)
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                    format!(
+                        "Sum of the output values: {}",
+                        output_values.iter().sum::<u64>()
+                    ),
+                ]
             }
-        }
+        })
+    }
+}
+
+/// Segments of each digit, with the standard wiring.
+const DIGIT_SEGMENTS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+/// Signal pattern of `digit` with `wiring`, in random order.
+fn scramble(rng: &mut Rng, wiring: &[char], digit: usize) -> String {
+    let mut pattern: Vec<char> = DIGIT_SEGMENTS[digit]
+        .bytes()
+        .map(|segment| wiring[usize::from(segment - b'a')])
+        .collect();
+    rng.shuffle(&mut pattern);
+
+    pattern.into_iter().collect()
+}
 
-        match digit_output_values
+/// Random notes of `size` displays, each with its own wiring.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut notes = String::new();
+
+    for _ in 0..size {
+        let mut wiring: Vec<char> = "abcdefg".chars().collect();
+        rng.shuffle(&mut wiring);
+
+        let mut digits: Vec<usize> = (0..10).collect();
+        rng.shuffle(&mut digits);
+
+        let signal_patterns: Vec<String> = digits
             .iter()
-            .map(|digit| match signal_patterns_map.get(&digit) {
-                Some(value) => value.to_string(),
-                None => String::from(""),
+            .map(|&digit| scramble(rng, &wiring, digit))
+            .collect();
+        let output_values: Vec<String> = (0..4)
+            .map(|_| {
+                let digit = rng.below(10) as usize;
+                scramble(rng, &wiring, digit)
             })
-            .collect::<Vec<String>>()
-            .join("")
-            .parse::<u64>()
-        {
-            Ok(number) => {
-                sum_of_digits += number;
-            }
-            Err(_error) => continue,
-        }
+            .collect();
+
+        notes.push_str(&format!(
+            "{} | {}\n",
+            signal_patterns.join(" "),
+            output_values.join(" ")
+        ));
     }
 
-    Ok(sum_of_digits)
+    notes
 }
 
 #[cfg(test)]
@@ -332,6 +450,10 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
     #[test]
     fn test_part1() {
         assert_eq!(part1(TEST_INPUT).unwrap(), 26);
+        assert_eq!(
+            part1("acedgfb cdfbe gcdfa fbcad dab"),
+            Err("Missing ' | ' between the signal patterns and the output value")
+        );
     }
 
     #[test]
@@ -70,6 +70,9 @@ What do you get if you multiply together the sizes of the three largest basins?
 
 use std::collections::HashMap;
 
+use crate::rng::Rng;
+use crate::{AocError, Explain};
+
 pub fn parse_input(input: &str) -> Vec<Vec<u64>> {
     input
         .split('\n')
@@ -92,8 +95,8 @@ pub fn lowest_points(map: Vec<Vec<u64>>) -> Vec<u64> {
 
             // Top row neighbors.
             if y != 0 {
-                if let Some(row) = map.get(y - 1) {
-                    neighbors.push(row[x])
+                if let Some(neighbor) = map.get(y - 1).and_then(|row| row.get(x)) {
+                    neighbors.push(*neighbor)
                 }
             }
 
@@ -108,8 +111,8 @@ pub fn lowest_points(map: Vec<Vec<u64>>) -> Vec<u64> {
             }
 
             // Bottom row neighbors.
-            if let Some(row) = map.get(y + 1) {
-                neighbors.push(row[x])
+            if let Some(neighbor) = map.get(y + 1).and_then(|row| row.get(x)) {
+                neighbors.push(*neighbor)
             }
 
             if let Some(minimum_neighbor) = neighbors.iter().min() {
@@ -128,7 +131,7 @@ pub struct Basins {
 }
 
 impl Basins {
-    pub fn new(map: &Vec<Vec<u64>>) -> Self {
+    pub fn new(map: &[Vec<u64>]) -> Self {
         let mut basins: HashMap<(usize, usize), u64> = HashMap::new();
         let mut current_basin: u64 = 0;
         let mut last_basin: u64 = 0;
@@ -185,8 +188,8 @@ impl Basins {
 
         // Top neighbor.
         if y > &0 {
-            if let Some(neighbor) = map.get(y - 1) {
-                if neighbor[*x] != 9 {
+            if let Some(neighbor) = map.get(y - 1).and_then(|row| row.get(*x)) {
+                if *neighbor != 9 {
                     neighbors.push((*y - 1, *x))
                 }
             }
@@ -207,8 +210,8 @@ impl Basins {
         }
 
         // Bottom neighbor.
-        if let Some(neighbor) = map.get(y + 1) {
-            if neighbor[*x] != 9 {
+        if let Some(neighbor) = map.get(y + 1).and_then(|row| row.get(*x)) {
+            if *neighbor != 9 {
                 neighbors.push((*y + 1, *x))
             }
         }
@@ -224,8 +227,9 @@ pub fn part1(input: &str) -> Result<u64, &'static str> {
         .sum())
 }
 
-pub fn part2(input: &str) -> Result<u64, &'static str> {
-    let basins = Basins::new(&parse_input(input));
+/// Size of each basin, largest first.
+fn basin_sizes(map: &[Vec<u64>]) -> Vec<u64> {
+    let basins = Basins::new(map);
 
     let frequencies_map =
         basins
@@ -236,19 +240,79 @@ pub fn part2(input: &str) -> Result<u64, &'static str> {
                 map
             });
 
-    let mut frequencies = frequencies_map.values().collect::<Vec<&u64>>();
+    let mut frequencies = frequencies_map.into_values().collect::<Vec<u64>>();
 
-    frequencies.sort();
+    frequencies.sort_by(|a, b| b.cmp(a));
+    frequencies
+}
 
-    Ok(frequencies.iter().copied().rev().take(3).product::<u64>())
+pub fn part2(input: &str) -> Result<u64, &'static str> {
+    Ok(basin_sizes(&parse_input(input))
+        .iter()
+        .take(3)
+        .product::<u64>())
     // Answer is 950600 and the code provides 474474
 }
 
+pub struct Explanation;
+
+impl Explain for Explanation {
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        Ok(match part {
+            1 => {
+                let lowest_points = lowest_points(parse_input(input));
+
+                vec![
+                    format!("{} low points", lowest_points.len()),
+                    format!(
+                        "Sum of their risk levels (height + 1): {}",
+                        lowest_points.iter().map(|point| point + 1).sum::<u64>()
+                    ),
+                ]
+            }
+            _ => {
+                let basin_sizes = basin_sizes(&parse_input(input));
+                let largest_basins = &basin_sizes[..basin_sizes.len().min(3)];
+
+                vec![
+                    format!("{} basins", basin_sizes.len()),
+                    format!(
+                        "Three largest basins: {} = {}",
+                        largest_basins
+                            .iter()
+                            .map(u64::to_string)
+                            .collect::<Vec<String>>()
+                            .join(" * "),
+                        largest_basins.iter().product::<u64>()
+                    ),
+                ]
+            }
+        })
+    }
+}
+
+/// Random heightmap of `size` by `size` locations, with walls of 9 between basins.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let mut row: String = (0..size)
+                .map(|_| match rng.chance(25) {
+                    true => '9',
+                    false => char::from(b'0' + rng.below(9) as u8),
+                })
+                .collect();
+            row.push('\n');
+            row
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
-    use crate::day9::{lowest_points, parse_input, part1, part2, Basins};
+    use crate::day9::{lowest_points, parse_input, part1, part2, Basins, Explanation};
+    use crate::Explain;
 
     static TEST_INPUT: &str = "2199943210
 3987894921
@@ -276,6 +340,14 @@ mod tests {
         assert_eq!(lowest_points(parse_input(TEST_INPUT)), vec![1, 0, 5, 5]);
     }
 
+    #[test]
+    fn test_lowest_points_uneven_rows() {
+        let map = vec![vec![5, 1, 5], vec![9]];
+
+        assert_eq!(lowest_points(map.clone()), vec![1]);
+        assert_eq!(Basins::new(&map).basins.len(), 3);
+    }
+
     #[test]
     fn test_basins_new() {
         assert_eq!(
@@ -323,7 +395,7 @@ mod tests {
     #[test]
     fn test_basins_new_line_1() {
         assert_eq!(
-            Basins::new(&vec![vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0]]).basins,
+            Basins::new(&[vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0]]).basins,
             HashMap::from([
                 ((0, 0), 0),
                 ((0, 1), 0),
@@ -339,7 +411,7 @@ mod tests {
     #[test]
     fn test_basins_new_line_1_and_2() {
         assert_eq!(
-            Basins::new(&vec![
+            Basins::new(&[
                 vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
                 vec![3, 9, 8, 7, 8, 9, 4, 9, 2, 1]
             ])
@@ -366,7 +438,7 @@ mod tests {
     #[test]
     fn test_basins_new_line_1_to_3() {
         assert_eq!(
-            Basins::new(&vec![
+            Basins::new(&[
                 vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
                 vec![3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
                 vec![9, 8, 5, 6, 7, 8, 9, 8, 9, 2]
@@ -451,4 +523,15 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok(1134));
     }
+
+    #[test]
+    fn test_explanation() {
+        assert_eq!(
+            Explanation::explain(2, TEST_INPUT),
+            Ok(vec![
+                String::from("4 basins"),
+                String::from("Three largest basins: 14 * 9 * 9 = 1134"),
+            ])
+        );
+    }
 }
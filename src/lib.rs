@@ -1,11 +1,419 @@
+use rng::Rng;
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "day1")]
 pub mod day1;
+#[cfg(feature = "day10")]
 pub mod day10;
+#[cfg(feature = "day11")]
 pub mod day11;
+#[cfg(feature = "day2")]
 pub mod day2;
+#[cfg(feature = "day3")]
 pub mod day3;
+#[cfg(feature = "day4")]
 pub mod day4;
+#[cfg(feature = "day5")]
 pub mod day5;
+#[cfg(feature = "day6")]
 pub mod day6;
+#[cfg(feature = "day7")]
 pub mod day7;
+#[cfg(feature = "day8")]
 pub mod day8;
+#[cfg(feature = "day9")]
 pub mod day9;
+pub mod rng;
+pub mod trace;
+
+/// Answer produced by a solver, whatever the integer type the day module uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Signed(value) => write!(f, "{}", value),
+            Answer::Unsigned(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::Signed(value.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Signed(value)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Unsigned(value.into())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Unsigned(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Unsigned(value as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AocError {
+    UnknownDay(u8),
+    UnknownPart(u8),
+    Solver(String),
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::UnknownDay(day) => write!(f, "no solver available for day {}", day),
+            AocError::UnknownPart(part) => {
+                write!(f, "part {} does not exist (expected 1 or 2)", part)
+            }
+            AocError::Solver(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for AocError {}
+
+impl From<&str> for AocError {
+    fn from(error: &str) -> Self {
+        AocError::Solver(error.to_string())
+    }
+}
+
+/// Step-by-step account of how a day reaches its answers, implemented by the
+/// `Explanation` type of each day module.
+pub trait Explain {
+    /// Lines describing how the answer of `part` (1 or 2) is reached for `input`.
+    fn explain(part: u8, input: &str) -> Result<Vec<String>, AocError>;
+}
+
+type Solver = fn(&str) -> Result<Answer, AocError>;
+type Explainer = fn(u8, &str) -> Result<Vec<String>, AocError>;
+type Generator = fn(&mut Rng, usize) -> String;
+
+/// A puzzle registered in the library, with its two solvers.
+pub struct Day {
+    pub day: u8,
+    pub title: &'static str,
+    /// Example input given in the puzzle description.
+    pub example: &'static str,
+    parts: [Solver; 2],
+    explainer: Explainer,
+    generator: Generator,
+}
+
+impl Day {
+    pub fn solve(&self, part: u8, input: &str) -> Result<Answer, AocError> {
+        match part {
+            1 | 2 => self.parts[usize::from(part) - 1](input),
+            _ => Err(AocError::UnknownPart(part)),
+        }
+    }
+
+    pub fn explain(&self, part: u8, input: &str) -> Result<Vec<String>, AocError> {
+        match part {
+            1 | 2 => (self.explainer)(part, input),
+            _ => Err(AocError::UnknownPart(part)),
+        }
+    }
+
+    /// Random input with `size` items (readings, commands, boards, lines, rows
+    /// of a grid...), the same for the same seed. There is at least one item.
+    pub fn generate(&self, size: usize, seed: u64) -> String {
+        (self.generator)(&mut Rng::new(seed), size.max(1))
+    }
+}
+
+static DAYS: &[Day] = &[
+    #[cfg(feature = "day1")]
+    Day {
+        day: 1,
+        title: "Sonar Sweep",
+        example: include_str!("input/examples/day1.txt"),
+        parts: [
+            |input| day1::part1(input).map(Answer::from).map_err(AocError::from),
+            |input| day1::part2(input).map(Answer::from).map_err(AocError::from),
+        ],
+        explainer: day1::Explanation::explain,
+        generator: day1::generate,
+    },
+    #[cfg(feature = "day2")]
+    Day {
+        day: 2,
+        title: "Dive!",
+        example: include_str!("input/examples/day2.txt"),
+        parts: [
+            |input| {
+                day2::part1(input)
+                    .map(Answer::from)
+                    .map_err(|error| AocError::Solver(error.to_string()))
+            },
+            |input| {
+                day2::part2(input)
+                    .map(Answer::from)
+                    .map_err(|error| AocError::Solver(error.to_string()))
+            },
+        ],
+        explainer: day2::Explanation::explain,
+        generator: day2::generate,
+    },
+    #[cfg(feature = "day3")]
+    Day {
+        day: 3,
+        title: "Binary Diagnostic",
+        example: include_str!("input/examples/day3.txt"),
+        parts: [
+            |input| day3::part1(input).map(Answer::from).map_err(AocError::from),
+            |input| day3::part2(input).map(Answer::from).map_err(AocError::from),
+        ],
+        explainer: day3::Explanation::explain,
+        generator: day3::generate,
+    },
+    #[cfg(feature = "day4")]
+    Day {
+        day: 4,
+        title: "Giant Squid",
+        example: include_str!("input/examples/day4.txt"),
+        parts: [
+            |input| day4::part1(input).map(Answer::from).map_err(AocError::from),
+            |input| day4::part2(input).map(Answer::from).map_err(AocError::from),
+        ],
+        explainer: day4::Explanation::explain,
+        generator: day4::generate,
+    },
+    #[cfg(feature = "day5")]
+    Day {
+        day: 5,
+        title: "Hydrothermal Venture",
+        example: include_str!("input/examples/day5.txt"),
+        parts: [
+            |input| day5::part1(input).map(Answer::from).map_err(AocError::from),
+            |input| day5::part2(input).map(Answer::from).map_err(AocError::from),
+        ],
+        explainer: day5::Explanation::explain,
+        generator: day5::generate,
+    },
+    #[cfg(feature = "day6")]
+    Day {
+        day: 6,
+        title: "Lanternfish",
+        example: include_str!("input/examples/day6.txt"),
+        parts: [
+            |input| day6::part1(input).map(Answer::from).map_err(AocError::from),
+            |input| day6::part2(input).map(Answer::from).map_err(AocError::from),
+        ],
+        explainer: day6::Explanation::explain,
+        generator: day6::generate,
+    },
+    #[cfg(feature = "day7")]
+    Day {
+        day: 7,
+        title: "The Treachery of Whales",
+        example: include_str!("input/examples/day7.txt"),
+        parts: [
+            |input| day7::part1(input).map(Answer::from).map_err(AocError::from),
+            |input| day7::part2(input).map(Answer::from).map_err(AocError::from),
+        ],
+        explainer: day7::Explanation::explain,
+        generator: day7::generate,
+    },
+    #[cfg(feature = "day8")]
+    Day {
+        day: 8,
+        title: "Seven Segment Search",
+        example: include_str!("input/examples/day8.txt"),
+        parts: [
+            |input| day8::part1(input).map(Answer::from).map_err(AocError::from),
+            |input| day8::part2(input).map(Answer::from).map_err(AocError::from),
+        ],
+        explainer: day8::Explanation::explain,
+        generator: day8::generate,
+    },
+    #[cfg(feature = "day9")]
+    Day {
+        day: 9,
+        title: "Smoke Basin",
+        example: include_str!("input/examples/day9.txt"),
+        parts: [
+            |input| day9::part1(input).map(Answer::from).map_err(AocError::from),
+            |input| day9::part2(input).map(Answer::from).map_err(AocError::from),
+        ],
+        explainer: day9::Explanation::explain,
+        generator: day9::generate,
+    },
+    #[cfg(feature = "day10")]
+    Day {
+        day: 10,
+        title: "Syntax Scoring",
+        example: include_str!("input/examples/day10.txt"),
+        parts: [
+            |input| {
+                day10::part1(input)
+                    .map(Answer::from)
+                    .map_err(AocError::from)
+            },
+            |input| {
+                day10::part2(input)
+                    .map(Answer::from)
+                    .map_err(AocError::from)
+            },
+        ],
+        explainer: day10::Explanation::explain,
+        generator: day10::generate,
+    },
+    #[cfg(feature = "day11")]
+    Day {
+        day: 11,
+        title: "Dumbo Octopus",
+        example: include_str!("input/examples/day11.txt"),
+        parts: [
+            |input| {
+                day11::part1(input)
+                    .map(Answer::from)
+                    .map_err(AocError::from)
+            },
+            |input| {
+                day11::part2(input)
+                    .map(Answer::from)
+                    .map_err(AocError::from)
+            },
+        ],
+        explainer: day11::Explanation::explain,
+        generator: day11::generate,
+    },
+];
+
+/// Every day that has a solver, in calendar order.
+pub fn days() -> &'static [Day] {
+    DAYS
+}
+
+pub fn find_day(day: u8) -> Result<&'static Day, AocError> {
+    DAYS.iter()
+        .find(|registered| registered.day == day)
+        .ok_or(AocError::UnknownDay(day))
+}
+
+/// Solve one part of one day, regardless of the return type of the day module.
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Answer, AocError> {
+    find_day(day)?.solve(part, input)
+}
+
+/// Reproducible random input of one day, see [`Day::generate`].
+pub fn generate(day: u8, size: usize, seed: u64) -> Result<String, AocError> {
+    Ok(find_day(day)?.generate(size, seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{days, generate, solve, Answer, AocError};
+
+    #[test]
+    fn test_days() {
+        assert!(days().windows(2).all(|pair| pair[0].day < pair[1].day));
+    }
+
+    #[cfg(all(feature = "day1", feature = "day11"))]
+    #[test]
+    fn test_days_all_features() {
+        assert_eq!(
+            days().iter().map(|day| day.day).collect::<Vec<u8>>(),
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+        );
+    }
+
+    #[test]
+    fn test_examples() {
+        for day in days() {
+            for part in 1..=2 {
+                assert!(day.solve(part, day.example).is_ok());
+                assert!(!day.explain(part, day.example).unwrap().is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate() {
+        for day in days() {
+            for seed in 0..5 {
+                for size in [1, 10] {
+                    let input = day.generate(size, seed);
+
+                    assert_eq!(input, day.generate(size, seed));
+                    for part in 1..=2 {
+                        assert!(day.solve(part, &input).is_ok());
+                    }
+                }
+            }
+        }
+        assert_eq!(generate(25, 10, 0), Err(AocError::UnknownDay(25)));
+    }
+
+    #[cfg(all(feature = "day1", feature = "day6"))]
+    #[test]
+    fn test_solve() {
+        assert_eq!(
+            solve(1, 1, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263"),
+            Ok(Answer::Signed(7))
+        );
+        assert_eq!(
+            solve(6, 2, "3,4,3,1,2\n"),
+            Ok(Answer::Unsigned(26984457539))
+        );
+    }
+
+    #[test]
+    fn test_solve_unknown_day_or_part() {
+        assert_eq!(solve(25, 1, ""), Err(AocError::UnknownDay(25)));
+        if let Some(day) = days().first() {
+            assert_eq!(day.solve(3, ""), Err(AocError::UnknownPart(3)));
+            assert_eq!(day.explain(0, ""), Err(AocError::UnknownPart(0)));
+        }
+    }
+
+    #[cfg(feature = "day10")]
+    #[test]
+    fn test_solve_solver_error() {
+        assert_eq!(
+            solve(10, 2, ""),
+            Err(AocError::Solver(String::from(
+                "Could not find middle score"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::Signed(-3).to_string(), "-3");
+        assert_eq!(Answer::Unsigned(42).to_string(), "42");
+        assert_eq!(Answer::Text(String::from("abc")).to_string(), "abc");
+    }
+}
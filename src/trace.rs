@@ -0,0 +1,233 @@
+//! Lightweight tracing of solver internals.
+//!
+//! Solvers report intermediate facts with [`trace_event!`](crate::trace_event),
+//! tagged with a level and a target (the day module, e.g. `"day4"`). Events are
+//! written to stderr when enabled, so answers on stdout are never affected. When
+//! tracing is off, an event costs a single atomic load and nothing is formatted.
+//!
+//! The filter is a comma-separated list of a default level and `target=level`
+//! overrides, e.g. `debug` or `warn,day11=trace`, read from the `AOC_LOG`
+//! environment variable by [`init_from_env`].
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+
+pub const ENV_VARIABLE: &str = "AOC_LOG";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+
+        f.pad(name)
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("unknown trace level: {}", level)),
+        }
+    }
+}
+
+/// Which events are enabled: a default level and per-target overrides.
+/// `None` disables events.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    default: Option<Level>,
+    targets: Vec<(String, Option<Level>)>,
+}
+
+fn parse_level(level: &str) -> Result<Option<Level>, String> {
+    match level {
+        "off" => Ok(None),
+        level => level.parse().map(Some),
+    }
+}
+
+impl Filter {
+    pub const fn off() -> Self {
+        Self {
+            default: None,
+            targets: Vec::new(),
+        }
+    }
+
+    pub fn level(level: Level) -> Self {
+        Self {
+            default: Some(level),
+            targets: Vec::new(),
+        }
+    }
+
+    pub fn allows(&self, level: Level, target: &str) -> bool {
+        let max_level = self
+            .targets
+            .iter()
+            .find(|(filtered_target, _)| filtered_target == target)
+            .map_or(self.default, |(_, max_level)| *max_level);
+
+        max_level.is_some_and(|max_level| level <= max_level)
+    }
+
+    fn max_level(&self) -> Option<Level> {
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .chain([self.default])
+            .max()
+            .flatten()
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::off();
+
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => filter
+                    .targets
+                    .push((target.trim().to_string(), parse_level(level.trim())?)),
+                None => filter.default = parse_level(directive)?,
+            }
+        }
+
+        Ok(filter)
+    }
+}
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+static FILTER: RwLock<Filter> = RwLock::new(Filter::off());
+
+pub fn set_filter(filter: Filter) {
+    MAX_LEVEL.store(
+        filter.max_level().map_or(0, |level| level as u8),
+        Ordering::Relaxed,
+    );
+    *FILTER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = filter;
+}
+
+/// Apply the filter of the `AOC_LOG` environment variable, if it is set.
+pub fn init_from_env() -> Result<(), String> {
+    match env::var(ENV_VARIABLE) {
+        Ok(spec) => {
+            set_filter(spec.parse()?);
+            Ok(())
+        }
+        Err(_) => Ok(()),
+    }
+}
+
+pub fn enabled(level: Level, target: &str) -> bool {
+    // Cheap check first, so disabled events never take the lock.
+    if level as u8 > MAX_LEVEL.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    FILTER
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .allows(level, target)
+}
+
+pub fn format_event(level: Level, target: &str, message: fmt::Arguments) -> String {
+    format!("[{:<5} {}] {}", level, target, message)
+}
+
+#[doc(hidden)]
+pub fn write_event(level: Level, target: &str, message: fmt::Arguments) {
+    eprintln!("{}", format_event(level, target, message));
+}
+
+/// Report an event from a solver, e.g.
+/// `trace_event!(Level::Debug, "day4", "board {} wins", index)`.
+#[macro_export]
+macro_rules! trace_event {
+    ($level:expr, $target:expr, $($message:tt)+) => {
+        if $crate::trace::enabled($level, $target) {
+            $crate::trace::write_event($level, $target, format_args!($($message)+));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trace::{enabled, format_event, set_filter, Filter, Level};
+
+    #[test]
+    fn test_level_from_str() {
+        assert_eq!("debug".parse(), Ok(Level::Debug));
+        assert_eq!("TRACE".parse(), Ok(Level::Trace));
+        assert!("verbose".parse::<Level>().is_err());
+    }
+
+    #[test]
+    fn test_filter_from_str() {
+        let filter: Filter = "warn,day11=trace,day4=off".parse().unwrap();
+
+        assert!(filter.allows(Level::Warn, "day10"));
+        assert!(!filter.allows(Level::Info, "day10"));
+        assert!(filter.allows(Level::Trace, "day11"));
+        assert!(!filter.allows(Level::Error, "day4"));
+        assert_eq!(filter.max_level(), Some(Level::Trace));
+        assert!("day1=loud".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn test_filter_off() {
+        let filter = Filter::off();
+
+        assert!(!filter.allows(Level::Error, "day1"));
+        assert_eq!(filter.max_level(), None);
+        assert_eq!("".parse(), Ok(Filter::off()));
+    }
+
+    #[test]
+    fn test_set_filter() {
+        set_filter("day8=debug".parse().unwrap());
+        assert!(enabled(Level::Debug, "day8"));
+        assert!(!enabled(Level::Trace, "day8"));
+        assert!(!enabled(Level::Error, "day9"));
+
+        set_filter(Filter::off());
+        assert!(!enabled(Level::Error, "day8"));
+    }
+
+    #[test]
+    fn test_format_event() {
+        assert_eq!(
+            format_event(Level::Info, "day4", format_args!("board {} wins", 2)),
+            "[INFO  day4] board 2 wins"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use aoc_rust_2021::days;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Output of the binary run without arguments from `folder`, which also
+/// stands for the user configuration folder.
+fn run_bare(folder: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc_rust_2021"))
+        .current_dir(folder)
+        .env("XDG_CONFIG_HOME", folder)
+        .env_remove("AOC_CACHE")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_bare_run_reads_the_configured_input_dir() {
+    let folder = env::temp_dir().join(format!("aoc_rust_2021-run-{}", std::process::id()));
+    fs::create_dir_all(&folder).unwrap();
+
+    #[cfg(not(feature = "embed-inputs"))]
+    {
+        let output = run_bare(&folder);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).starts_with("missing input folder"));
+    }
+
+    let day = days()[0].day;
+    let input_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("input");
+    fs::write(
+        folder.join("aoc.toml"),
+        format!(
+            "input_dir = {:?}\ndays = [{}]\ncolor = \"never\"\n",
+            input_dir.display().to_string(),
+            day
+        ),
+    )
+    .unwrap();
+    let output = run_bare(&folder);
+    fs::remove_dir_all(&folder).unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .contains(&format!("Solution of Day {}, Part 2:", day)));
+}
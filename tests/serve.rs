@@ -0,0 +1,114 @@
+use aoc_rust_2021::days;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+/// Server started on an ephemeral localhost port, stopped when dropped.
+struct Server {
+    process: Child,
+    address: String,
+}
+
+impl Server {
+    fn start() -> Self {
+        let mut process = Command::new(env!("CARGO_BIN_EXE_aoc_rust_2021"))
+            .args(["serve", "--address", "127.0.0.1:0"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut banner = String::new();
+        BufReader::new(process.stdout.take().unwrap())
+            .read_line(&mut banner)
+            .unwrap();
+        let address = banner
+            .trim()
+            .strip_prefix("Listening on http://")
+            .unwrap()
+            .to_string();
+
+        Self { process, address }
+    }
+
+    fn request(&self, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(&self.address).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            self.address,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+
+        (status, body.to_string())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.process.kill().ok();
+        self.process.wait().ok();
+    }
+}
+
+#[test]
+fn test_serve_solve() {
+    let server = Server::start();
+
+    for day in days() {
+        let (status, body) =
+            server.request("POST", &format!("/days/{}/parts/2", day.day), day.example);
+
+        assert_eq!(status, 200);
+        assert!(body.starts_with(&format!(
+            "{{\"day\":{},\"part\":2,\"answer\":\"{}\",\"time_us\":",
+            day.day,
+            day.solve(2, day.example).unwrap()
+        )));
+    }
+}
+
+#[test]
+fn test_serve_list_days() {
+    let server = Server::start();
+    let (status, body) = server.request("GET", "/days", "");
+
+    assert_eq!(status, 200);
+    for day in days() {
+        assert!(body.contains(&format!(
+            "{{\"day\":{},\"title\":\"{}\"}}",
+            day.day, day.title
+        )));
+    }
+}
+
+#[test]
+fn test_serve_errors() {
+    let server = Server::start();
+
+    assert_eq!(
+        server.request("POST", "/days/30/parts/1", ""),
+        (
+            404,
+            String::from("{\"error\":\"no solver available for day 30\"}")
+        )
+    );
+    assert_eq!(server.request("DELETE", "/days", "").0, 405);
+    #[cfg(feature = "day10")]
+    assert_eq!(
+        server.request("POST", "/days/10/parts/2", ""),
+        (
+            422,
+            String::from("{\"error\":\"Could not find middle score\"}")
+        )
+    );
+}